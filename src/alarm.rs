@@ -0,0 +1,127 @@
+use crate::{DateTime, Weekday};
+
+/// One of the two independent alarm modules
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Alarm {
+    Zero,
+    One,
+}
+
+impl Alarm {
+    pub(crate) fn base_register(self) -> u8 {
+        match self {
+            Alarm::Zero => 0x0A,
+            Alarm::One => 0x11,
+        }
+    }
+}
+
+/// Interrupt flags (ALMxIF) of both alarms
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AlarmFlags {
+    pub alarm0: bool,
+    pub alarm1: bool,
+}
+
+impl AlarmFlags {
+    pub fn is_set(&self, alarm: Alarm) -> bool {
+        match alarm {
+            Alarm::Zero => self.alarm0,
+            Alarm::One => self.alarm1,
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.alarm0 || self.alarm1
+    }
+}
+
+/// Which fields must match the current time for an alarm to assert (ALMxMSK)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AlarmMatch {
+    Seconds,
+    Minutes,
+    Hours,
+    Weekday,
+    Date,
+    /// Seconds, minutes, hours, weekday, date and month
+    Full,
+}
+
+impl AlarmMatch {
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            AlarmMatch::Seconds => 0b000,
+            AlarmMatch::Minutes => 0b001,
+            AlarmMatch::Hours => 0b010,
+            AlarmMatch::Weekday => 0b011,
+            AlarmMatch::Date => 0b100,
+            AlarmMatch::Full => 0b111,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b000 => Some(AlarmMatch::Seconds),
+            0b001 => Some(AlarmMatch::Minutes),
+            0b010 => Some(AlarmMatch::Hours),
+            0b011 => Some(AlarmMatch::Weekday),
+            0b100 => Some(AlarmMatch::Date),
+            0b111 => Some(AlarmMatch::Full),
+            _ => None,
+        }
+    }
+}
+
+/// Asserted level of the MFP pin when an alarm fires (ALMPOL), shared by both alarms
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AlarmPolarity {
+    ActiveLow,
+    ActiveHigh,
+}
+
+/// Match values for an alarm. Fields not covered by `matches` are still written to the
+/// device but are ignored by the comparator.
+///
+/// The weekday is stored using the driver's [`first_weekday`](crate::Mcp7940n::first_weekday)
+/// mapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AlarmConfig {
+    pub matches: AlarmMatch,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub weekday: Weekday,
+    pub day: u8,
+    pub month: u8,
+}
+
+impl AlarmConfig {
+    /// Builds an alarm matching the given fields of `datetime`. The year is ignored as the
+    /// alarm registers do not store one.
+    pub fn new(matches: AlarmMatch, datetime: &DateTime) -> Self {
+        Self {
+            matches,
+            hour: datetime.hour(),
+            minute: datetime.minute(),
+            second: datetime.second(),
+            weekday: datetime.weekday(),
+            day: datetime.day(),
+            month: datetime.month(),
+        }
+    }
+
+    /// Whether every field is within the range the alarm registers can hold
+    pub(crate) fn is_valid(&self) -> bool {
+        self.hour <= 23
+            && self.minute <= 59
+            && self.second <= 59
+            && (1..=31).contains(&self.day)
+            && (1..=12).contains(&self.month)
+    }
+}
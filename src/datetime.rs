@@ -0,0 +1,507 @@
+//! Date and time types that don't depend on any date library.
+//!
+//! Conversions to and from `chrono`, `time` and `jiff` are available behind the cargo feature of
+//! the same name.
+
+/// Day of the week
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// Number of days since Monday, from 0 to 6
+    pub fn days_since_monday(self) -> u8 {
+        self as u8
+    }
+
+    /// Number of days from `other` forward to `self`, from 0 to 6
+    pub fn days_since(self, other: Weekday) -> u8 {
+        (self.days_since_monday() + 7 - other.days_since_monday()) % 7
+    }
+
+    /// The following day
+    pub fn succ(self) -> Self {
+        Self::from_days_since_monday(self.days_since_monday() + 1)
+    }
+
+    pub(crate) fn from_days_since_monday(days: u8) -> Self {
+        match days % 7 {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+}
+
+/// A date and time of day without a time zone, from year 0 to 9999
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    /// Returns `None` if the date or time doesn't exist or the year is past 9999
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Option<Self> {
+        if year > 9999
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return None;
+        }
+
+        Some(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn year(self) -> u16 {
+        self.year
+    }
+
+    pub fn month(self) -> u8 {
+        self.month
+    }
+
+    pub fn day(self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(self) -> u8 {
+        self.second
+    }
+
+    /// Builds the date and time `timestamp` seconds after 1970-01-01 00:00:00, or `None` if it
+    /// falls outside of years 0 through 9999
+    pub fn from_unix_timestamp(timestamp: i64) -> Option<Self> {
+        let days = timestamp.div_euclid(SECONDS_PER_DAY);
+        let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(i32::try_from(days).ok()?)?;
+
+        Self::new(
+            year,
+            month,
+            day,
+            (seconds / 3600) as u8,
+            (seconds / 60 % 60) as u8,
+            (seconds % 60) as u8,
+        )
+    }
+
+    /// Seconds since 1970-01-01 00:00:00, negative for earlier times
+    pub fn unix_timestamp(self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) as i64 * SECONDS_PER_DAY
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64
+    }
+
+    /// Adds `seconds`, which may be negative, carrying into the date. Returns `None` if the
+    /// result falls outside of years 0 through 9999.
+    pub fn checked_add_seconds(self, seconds: i64) -> Option<Self> {
+        Self::from_unix_timestamp(self.unix_timestamp().checked_add(seconds)?)
+    }
+
+    pub fn weekday(self) -> Weekday {
+        weekday_from_days(days_from_civil(self.year, self.month, self.day))
+    }
+}
+
+/// The date or time can't be represented by [`DateTime`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OutOfRange;
+
+pub(crate) const SECONDS_PER_DAY: i64 = 86_400;
+
+pub(crate) fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+pub(crate) fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days from 1970-01-01 to the given date, negative for earlier dates
+pub(crate) fn days_from_civil(year: u16, month: u8, day: u8) -> i32 {
+    // Shift the year to start in March so the leap day lands at the end of it
+    let year = year as i32 - (month <= 2) as i32;
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i32;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i32 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Weekday of the date `days` after 1970-01-01
+pub(crate) fn weekday_from_days(days: i32) -> Weekday {
+    // Day 0 of the civil day count, 1970-01-01, was a Thursday
+    Weekday::from_days_since_monday((days + 3).rem_euclid(7) as u8)
+}
+
+/// Date `days` after 1970-01-01 as year, month and day, the inverse of [`days_from_civil`].
+/// Returns `None` for dates before year 0.
+fn civil_from_days(days: i32) -> Option<(u16, u8, u8)> {
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (year_of_era * 365 + year_of_era / 4 - year_of_era / 100);
+    // Months counted from March, as in days_from_civil
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = era * 400 + year_of_era + (month <= 2) as i64;
+
+    Some((u16::try_from(year).ok()?, month as u8, day as u8))
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impls {
+    use super::{DateTime, OutOfRange, Weekday};
+    use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+
+    impl TryFrom<NaiveDateTime> for DateTime {
+        type Error = OutOfRange;
+
+        fn try_from(datetime: NaiveDateTime) -> Result<Self, Self::Error> {
+            DateTime::new(
+                u16::try_from(datetime.year()).map_err(|_| OutOfRange)?,
+                datetime.month() as u8,
+                datetime.day() as u8,
+                datetime.hour() as u8,
+                datetime.minute() as u8,
+                datetime.second() as u8,
+            )
+            .ok_or(OutOfRange)
+        }
+    }
+
+    impl From<DateTime> for NaiveDateTime {
+        fn from(datetime: DateTime) -> Self {
+            NaiveDate::from_ymd_opt(
+                datetime.year as i32,
+                datetime.month as u32,
+                datetime.day as u32,
+            )
+            .and_then(|date| {
+                date.and_hms_opt(
+                    datetime.hour as u32,
+                    datetime.minute as u32,
+                    datetime.second as u32,
+                )
+            })
+            .expect("DateTime is always a valid date and time")
+        }
+    }
+
+    impl From<chrono::Weekday> for Weekday {
+        fn from(weekday: chrono::Weekday) -> Self {
+            Weekday::from_days_since_monday(weekday.num_days_from_monday() as u8)
+        }
+    }
+
+    impl From<Weekday> for chrono::Weekday {
+        fn from(weekday: Weekday) -> Self {
+            match weekday {
+                Weekday::Monday => chrono::Weekday::Mon,
+                Weekday::Tuesday => chrono::Weekday::Tue,
+                Weekday::Wednesday => chrono::Weekday::Wed,
+                Weekday::Thursday => chrono::Weekday::Thu,
+                Weekday::Friday => chrono::Weekday::Fri,
+                Weekday::Saturday => chrono::Weekday::Sat,
+                Weekday::Sunday => chrono::Weekday::Sun,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impls {
+    use super::{DateTime, OutOfRange, Weekday};
+    use time::{Date, Month, PrimitiveDateTime, Time};
+
+    impl TryFrom<PrimitiveDateTime> for DateTime {
+        type Error = OutOfRange;
+
+        fn try_from(datetime: PrimitiveDateTime) -> Result<Self, Self::Error> {
+            DateTime::new(
+                u16::try_from(datetime.year()).map_err(|_| OutOfRange)?,
+                datetime.month() as u8,
+                datetime.day(),
+                datetime.hour(),
+                datetime.minute(),
+                datetime.second(),
+            )
+            .ok_or(OutOfRange)
+        }
+    }
+
+    impl From<DateTime> for PrimitiveDateTime {
+        fn from(datetime: DateTime) -> Self {
+            Month::try_from(datetime.month)
+                .ok()
+                .and_then(|month| {
+                    Date::from_calendar_date(datetime.year as i32, month, datetime.day).ok()
+                })
+                .zip(Time::from_hms(datetime.hour, datetime.minute, datetime.second).ok())
+                .map(|(date, time)| PrimitiveDateTime::new(date, time))
+                .expect("DateTime is always a valid date and time")
+        }
+    }
+
+    impl From<time::Weekday> for Weekday {
+        fn from(weekday: time::Weekday) -> Self {
+            Weekday::from_days_since_monday(weekday.number_days_from_monday())
+        }
+    }
+
+    impl From<Weekday> for time::Weekday {
+        fn from(weekday: Weekday) -> Self {
+            match weekday {
+                Weekday::Monday => time::Weekday::Monday,
+                Weekday::Tuesday => time::Weekday::Tuesday,
+                Weekday::Wednesday => time::Weekday::Wednesday,
+                Weekday::Thursday => time::Weekday::Thursday,
+                Weekday::Friday => time::Weekday::Friday,
+                Weekday::Saturday => time::Weekday::Saturday,
+                Weekday::Sunday => time::Weekday::Sunday,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "jiff")]
+mod jiff_impls {
+    use super::{DateTime, OutOfRange, Weekday};
+    use jiff::civil;
+
+    impl TryFrom<civil::DateTime> for DateTime {
+        type Error = OutOfRange;
+
+        fn try_from(datetime: civil::DateTime) -> Result<Self, Self::Error> {
+            DateTime::new(
+                u16::try_from(datetime.year()).map_err(|_| OutOfRange)?,
+                datetime.month() as u8,
+                datetime.day() as u8,
+                datetime.hour() as u8,
+                datetime.minute() as u8,
+                datetime.second() as u8,
+            )
+            .ok_or(OutOfRange)
+        }
+    }
+
+    impl From<DateTime> for civil::DateTime {
+        fn from(datetime: DateTime) -> Self {
+            civil::DateTime::new(
+                datetime.year as i16,
+                datetime.month as i8,
+                datetime.day as i8,
+                datetime.hour as i8,
+                datetime.minute as i8,
+                datetime.second as i8,
+                0,
+            )
+            .expect("DateTime is always a valid date and time")
+        }
+    }
+
+    impl From<civil::Weekday> for Weekday {
+        fn from(weekday: civil::Weekday) -> Self {
+            Weekday::from_days_since_monday(weekday.to_monday_zero_offset() as u8)
+        }
+    }
+
+    impl From<Weekday> for civil::Weekday {
+        fn from(weekday: Weekday) -> Self {
+            match weekday {
+                Weekday::Monday => civil::Weekday::Monday,
+                Weekday::Tuesday => civil::Weekday::Tuesday,
+                Weekday::Wednesday => civil::Weekday::Wednesday,
+                Weekday::Thursday => civil::Weekday::Thursday,
+                Weekday::Friday => civil::Weekday::Friday,
+                Weekday::Saturday => civil::Weekday::Saturday,
+                Weekday::Sunday => civil::Weekday::Sunday,
+            }
+        }
+    }
+}
+
+/// Shorthand for a [`DateTime`] that is known to be valid, for the unit tests
+#[cfg(test)]
+pub(crate) fn datetime(
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+) -> DateTime {
+    DateTime::new(year, month, day, hour, minute, second).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_from_days, datetime, days_from_civil, DateTime, Weekday};
+
+    #[test]
+    fn civil_days_around_epoch() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(1970, 1, 2), 1);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(civil_from_days(0), Some((1970, 1, 1)));
+        assert_eq!(civil_from_days(1), Some((1970, 1, 2)));
+        assert_eq!(civil_from_days(-1), Some((1969, 12, 31)));
+    }
+
+    #[test]
+    fn civil_days_at_range_limits() {
+        assert_eq!(days_from_civil(0, 1, 1), -719_528);
+        assert_eq!(days_from_civil(9999, 12, 31), 2_932_896);
+        assert_eq!(civil_from_days(-719_528), Some((0, 1, 1)));
+        assert_eq!(civil_from_days(2_932_896), Some((9999, 12, 31)));
+        assert_eq!(civil_from_days(-719_529), None);
+    }
+
+    #[test]
+    fn civil_days_round_trip() {
+        for days in -719_528..=2_932_896 {
+            let (year, month, day) = civil_from_days(days).unwrap();
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+    }
+
+    #[test]
+    fn unix_timestamp_around_epoch() {
+        assert_eq!(
+            DateTime::from_unix_timestamp(0),
+            Some(datetime(1970, 1, 1, 0, 0, 0))
+        );
+        assert_eq!(
+            DateTime::from_unix_timestamp(86_400),
+            Some(datetime(1970, 1, 2, 0, 0, 0))
+        );
+        assert_eq!(
+            DateTime::from_unix_timestamp(-1),
+            Some(datetime(1969, 12, 31, 23, 59, 59))
+        );
+        assert_eq!(
+            DateTime::from_unix_timestamp(-86_400),
+            Some(datetime(1969, 12, 31, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn unix_timestamp_at_range_limits() {
+        assert_eq!(datetime(0, 1, 1, 0, 0, 0).unix_timestamp(), -62_167_219_200);
+        assert_eq!(
+            datetime(9999, 12, 31, 23, 59, 59).unix_timestamp(),
+            253_402_300_799
+        );
+        assert_eq!(DateTime::from_unix_timestamp(-62_167_219_201), None);
+        assert_eq!(DateTime::from_unix_timestamp(253_402_300_800), None);
+        assert_eq!(DateTime::from_unix_timestamp(i64::MIN), None);
+        assert_eq!(DateTime::from_unix_timestamp(i64::MAX), None);
+    }
+
+    #[test]
+    fn unix_timestamp_leap_days() {
+        assert_eq!(
+            DateTime::from_unix_timestamp(951_782_400),
+            Some(datetime(2000, 2, 29, 0, 0, 0))
+        );
+        assert_eq!(
+            DateTime::from_unix_timestamp(1_709_251_199),
+            Some(datetime(2024, 2, 29, 23, 59, 59))
+        );
+        assert_eq!(
+            DateTime::from_unix_timestamp(4_107_542_400),
+            Some(datetime(2100, 3, 1, 0, 0, 0))
+        );
+        assert_eq!(
+            datetime(2100, 3, 1, 0, 0, 0).unix_timestamp(),
+            4_107_542_400
+        );
+        assert_eq!(DateTime::new(2100, 2, 29, 0, 0, 0), None);
+    }
+
+    #[test]
+    fn add_seconds_carries() {
+        assert_eq!(
+            datetime(2023, 12, 31, 23, 59, 59).checked_add_seconds(1),
+            Some(datetime(2024, 1, 1, 0, 0, 0))
+        );
+        assert_eq!(
+            datetime(2024, 3, 1, 0, 0, 0).checked_add_seconds(-1),
+            Some(datetime(2024, 2, 29, 23, 59, 59))
+        );
+        assert_eq!(
+            datetime(2023, 3, 1, 0, 0, 0).checked_add_seconds(-1),
+            Some(datetime(2023, 2, 28, 23, 59, 59))
+        );
+        assert_eq!(
+            datetime(9999, 12, 31, 23, 59, 59).checked_add_seconds(1),
+            None
+        );
+        assert_eq!(datetime(0, 1, 1, 0, 0, 0).checked_add_seconds(-1), None);
+        assert_eq!(
+            datetime(2024, 1, 1, 0, 0, 0).checked_add_seconds(i64::MAX),
+            None
+        );
+    }
+
+    #[test]
+    fn weekday() {
+        assert_eq!(datetime(1970, 1, 1, 0, 0, 0).weekday(), Weekday::Thursday);
+        assert_eq!(datetime(0, 1, 1, 0, 0, 0).weekday(), Weekday::Saturday);
+        assert_eq!(datetime(2024, 2, 29, 0, 0, 0).weekday(), Weekday::Thursday);
+        assert_eq!(datetime(9999, 12, 31, 0, 0, 0).weekday(), Weekday::Friday);
+    }
+}
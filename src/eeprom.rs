@@ -0,0 +1,249 @@
+//! Access to the EEPROM at I2C address `0x57` on the parts that have one.
+
+use core::marker::PhantomData;
+
+use embedded_hal::i2c::{Error as _, ErrorKind, I2c};
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+use crate::variant::{FactoryEui48, FactoryEui64, ProtectedEeprom, UserEeprom};
+#[cfg(feature = "async")]
+use crate::Mcp7940nAsync;
+use crate::{Error, Eui48, Eui64, Mcp7940n, EEPROM_PAGE_SIZE, EEPROM_SIZE, PROTECTED_EEPROM_SIZE};
+
+/// Start of the 8 byte protected EEPROM block
+const PROTECTED_START: u8 = 0xF0;
+
+/// EEPROM STATUS register holding the block protect bits
+const STATUS: u8 = 0xFF;
+
+/// EEUNLOCK register in the RTCC address space, written with 0x55 then 0xAA to allow one write
+/// to the protected block
+const EEUNLOCK: u8 = 0x09;
+
+/// Number of times the EEPROM is polled for an ACK after a write. The internal write cycle takes
+/// up to 5 ms and each poll is a full I2C transaction, so this covers it at 400 kHz with margin.
+const WRITE_POLLS: usize = 1000;
+
+/// Range of the EEPROM that ignores writes (BP1:BP0 in STATUS)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BlockProtect {
+    #[default]
+    None,
+    /// 0x60 through 0x7F
+    UpperQuarter,
+    /// 0x40 through 0x7F
+    UpperHalf,
+    /// 0x00 through 0x7F
+    All,
+}
+
+impl BlockProtect {
+    fn bits(self) -> u8 {
+        match self {
+            BlockProtect::None => 0b00,
+            BlockProtect::UpperQuarter => 0b01,
+            BlockProtect::UpperHalf => 0b10,
+            BlockProtect::All => 0b11,
+        }
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => BlockProtect::None,
+            0b01 => BlockProtect::UpperQuarter,
+            0b10 => BlockProtect::UpperHalf,
+            _ => BlockProtect::All,
+        }
+    }
+}
+
+/// EEPROM handle borrowing the bus from the RTC driver, see
+/// [`Mcp7940n::eeprom`](crate::Mcp7940n::eeprom)
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(Eeprom(async = "EepromAsync"), I2c(async = "AsyncI2c"))
+    )
+)]
+#[derive(Debug)]
+pub struct Eeprom<'a, I, V> {
+    i2c: &'a mut I,
+    variant: PhantomData<V>,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(
+            Eeprom(async = "EepromAsync"),
+            Mcp7940n(async = "Mcp7940nAsync"),
+            I2c(async = "AsyncI2c")
+        )
+    )
+)]
+impl<'a, I: I2c, V: ProtectedEeprom> Eeprom<'a, I, V> {
+    const ADDRESS: u8 = 0b101_0111;
+
+    pub(crate) fn new(i2c: &'a mut I) -> Self {
+        Self {
+            i2c,
+            variant: PhantomData,
+        }
+    }
+
+    /// Reads the whole protected block, 0xF0 through 0xF7
+    pub async fn read_protected(&mut self) -> Result<[u8; PROTECTED_EEPROM_SIZE], Error<I::Error>> {
+        let mut data = [0u8; PROTECTED_EEPROM_SIZE];
+        self.read(PROTECTED_START, &mut data).await?;
+
+        Ok(data)
+    }
+
+    /// Reads the factory-programmed EUI-48 from 0xF2 through 0xF7. Fails with
+    /// [`Error::InvalidEui`] if the block was erased or overwritten with a group address.
+    pub async fn eui48(&mut self) -> Result<Eui48, Error<I::Error>>
+    where
+        V: FactoryEui48,
+    {
+        let mut data = [0u8; 6];
+        self.read(PROTECTED_START + 2, &mut data).await?;
+
+        Eui48::new(data).ok_or(Error::InvalidEui)
+    }
+
+    /// Reads the factory-programmed EUI-64 from 0xF0 through 0xF7. Fails with
+    /// [`Error::InvalidEui`] if the block was erased or overwritten with a group address.
+    pub async fn eui64(&mut self) -> Result<Eui64, Error<I::Error>>
+    where
+        V: FactoryEui64,
+    {
+        let data = self.read_protected().await?;
+
+        Eui64::new(data).ok_or(Error::InvalidEui)
+    }
+
+    /// Unlocks and writes the whole protected block. This overwrites any factory-programmed
+    /// EUI, which can't be restored afterwards.
+    pub async fn write_protected(
+        &mut self,
+        data: &[u8; PROTECTED_EEPROM_SIZE],
+    ) -> Result<(), Error<I::Error>> {
+        // The unlock sequence goes to the RTCC address and only holds for the next write
+        let rtc_address = Mcp7940n::<I, V>::ADDRESS;
+        for key in [0x55, 0xAA] {
+            self.i2c
+                .write(rtc_address, &[EEUNLOCK, key])
+                .await
+                .map_err(Error::Bus)?;
+        }
+
+        let mut buf = [0u8; PROTECTED_EEPROM_SIZE + 1];
+        buf[0] = PROTECTED_START;
+        buf[1..].copy_from_slice(data);
+
+        self.write_page(&buf).await
+    }
+
+    async fn read(&mut self, address: u8, data: &mut [u8]) -> Result<(), Error<I::Error>> {
+        self.i2c
+            .write_read(Self::ADDRESS, &[address], data)
+            .await
+            .map_err(Error::Bus)
+    }
+
+    /// Writes `data`, the first byte being the address, and waits for the write cycle to finish
+    async fn write_page(&mut self, data: &[u8]) -> Result<(), Error<I::Error>> {
+        self.i2c
+            .write(Self::ADDRESS, data)
+            .await
+            .map_err(Error::Bus)?;
+
+        self.wait_for_write().await
+    }
+
+    /// Polls the EEPROM until it acknowledges its address again, which it doesn't do while an
+    /// internal write cycle is in progress
+    async fn wait_for_write(&mut self) -> Result<(), Error<I::Error>> {
+        let mut data = [0u8];
+        for _ in 0..WRITE_POLLS {
+            match self.i2c.read(Self::ADDRESS, &mut data).await {
+                Ok(()) => return Ok(()),
+                Err(error) if matches!(error.kind(), ErrorKind::NoAcknowledge(_)) => {}
+                Err(error) => return Err(Error::Bus(error)),
+            }
+        }
+
+        Err(Error::EepromWriteTimeout)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(Eeprom(async = "EepromAsync"), I2c(async = "AsyncI2c"))
+    )
+)]
+impl<I: I2c, V: UserEeprom> Eeprom<'_, I, V> {
+    /// Reads `data.len()` bytes of EEPROM starting at `address`
+    pub async fn read_eeprom(
+        &mut self,
+        address: u8,
+        data: &mut [u8],
+    ) -> Result<(), Error<I::Error>> {
+        if address as usize + data.len() > EEPROM_SIZE {
+            return Err(Error::EepromOutOfBounds);
+        }
+
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.read(address, data).await
+    }
+
+    /// Writes `data` to EEPROM starting at `address`, split into one write cycle per page it
+    /// touches. Writes to a block protected range are silently ignored by the device.
+    pub async fn write_eeprom(&mut self, address: u8, data: &[u8]) -> Result<(), Error<I::Error>> {
+        if address as usize + data.len() > EEPROM_SIZE {
+            return Err(Error::EepromOutOfBounds);
+        }
+
+        let mut address = address as usize;
+        let mut data = data;
+        while !data.is_empty() {
+            // A write past the end of a page wraps around to its start, so stop at the boundary
+            let len = data
+                .len()
+                .min(EEPROM_PAGE_SIZE - address % EEPROM_PAGE_SIZE);
+            let mut buf = [0u8; EEPROM_PAGE_SIZE + 1];
+            buf[0] = address as u8;
+            buf[1..=len].copy_from_slice(&data[..len]);
+
+            self.write_page(&buf[..=len]).await?;
+
+            address += len;
+            data = &data[len..];
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_block_protect(
+        &mut self,
+        protect: BlockProtect,
+    ) -> Result<(), Error<I::Error>> {
+        self.write_page(&[STATUS, protect.bits() << 2]).await
+    }
+
+    pub async fn block_protect(&mut self) -> Result<BlockProtect, Error<I::Error>> {
+        let mut data = [0u8];
+        self.read(STATUS, &mut data).await?;
+
+        Ok(BlockProtect::from_bits(data[0] >> 2))
+    }
+}
@@ -0,0 +1,39 @@
+/// Errors returned by the driver
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// The underlying I2C bus returned an error
+    Bus(E),
+    /// A register field held an invalid encoding, such as a BCD digit above 9
+    InvalidBcd,
+    /// A date or time that doesn't exist was read from the registers or passed in
+    InvalidDate,
+    /// The year can't be represented by the device
+    YearOutOfRange,
+    /// LPYR doesn't match whether the decoded year is a leap year
+    LeapYearMismatch,
+    /// The oscillator is disabled, so the timekeeping registers are not advancing
+    OscillatorNotRunning,
+    /// OSCRUN didn't set within the timeout after the oscillator was enabled
+    OscillatorStartTimeout,
+    /// OSCRUN set but dropped again while the oscillator was starting
+    OscillatorFailed,
+    /// OSCRUN didn't clear after the oscillator was stopped
+    OscillatorStopTimeout,
+    /// The time registers kept changing between consecutive reads
+    InconsistentRead,
+    /// ALMxMSK held one of the reserved match settings
+    InvalidAlarmMask,
+    /// The requested MFP mode can't take effect with the current alarm or trim configuration
+    MfpConflict,
+    /// The access doesn't fit within the 64 bytes of SRAM
+    SramOutOfBounds,
+    /// The requested trim exceeds what OSCTRIM can hold in the selected trim mode
+    TrimOutOfRange,
+    /// The EUI block doesn't hold a unicast address, so it was erased or never programmed
+    InvalidEui,
+    /// The access doesn't fit within the 128 bytes of EEPROM
+    EepromOutOfBounds,
+    /// The EEPROM didn't acknowledge its address again after a write
+    EepromWriteTimeout,
+}
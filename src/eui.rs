@@ -0,0 +1,63 @@
+/// EUI-48 node address, such as an Ethernet MAC address
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Eui48([u8; 6]);
+
+/// EUI-64 node address
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Eui64([u8; 8]);
+
+impl Eui48 {
+    /// Returns `None` if the OUI is blank or not a unicast address
+    pub fn new(bytes: [u8; 6]) -> Option<Self> {
+        valid_oui(&bytes).then_some(Self(bytes))
+    }
+
+    /// Organizationally unique identifier, the first three bytes
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 6] {
+        &self.0
+    }
+}
+
+impl Eui64 {
+    /// Returns `None` if the OUI is blank or not a unicast address
+    pub fn new(bytes: [u8; 8]) -> Option<Self> {
+        valid_oui(&bytes).then_some(Self(bytes))
+    }
+
+    /// Organizationally unique identifier, the first three bytes
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        &self.0
+    }
+}
+
+impl From<Eui48> for [u8; 6] {
+    fn from(eui: Eui48) -> Self {
+        eui.0
+    }
+}
+
+impl From<Eui64> for [u8; 8] {
+    fn from(eui: Eui64) -> Self {
+        eui.0
+    }
+}
+
+fn valid_oui(bytes: &[u8]) -> bool {
+    let oui = &bytes[..3];
+
+    // An unprogrammed block reads back as all 0s or all 1s, and bit 0 of the first byte marks a
+    // group address, which can't identify a single node
+    !oui.iter().all(|&byte| byte == 0x00)
+        && !oui.iter().all(|&byte| byte == 0xFF)
+        && oui[0] & 0x01 == 0
+}
@@ -1,27 +1,192 @@
 #![no_std]
 
-use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use core::marker::PhantomData;
+use core::time::Duration;
+use embedded_hal::delay::DelayNs;
 use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs as AsyncDelayNs;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+use registers::{
+    alarm_reg, power_fail_reg, reg, AlarmRegister, AlmDate, AlmHour, AlmMin, AlmMth, AlmSec,
+    AlmWkday, Control, OscTrim, PowerFailEvent, PowerFailRegister, PwrDate, PwrHour, PwrMin,
+    PwrMth, Register, RegisterDump, RtcDate, RtcHour, RtcMin, RtcMth, RtcSec, RtcWkday, RtcYear,
+    REGISTER_DUMP_SIZE,
+};
+use variant::{BatteryBackup, Mcp7940N, ProtectedEeprom, Variant};
 
+mod alarm;
+mod datetime;
+mod eeprom;
+mod error;
+mod eui;
+mod output;
+mod power;
+mod status;
+mod timezone;
+mod trim;
+mod zoned;
+
+#[cfg(feature = "rtcc")]
+mod rtcc;
+
+pub mod registers;
+#[cfg(feature = "sim")]
+pub mod sim;
+pub mod variant;
+
+pub use alarm::{Alarm, AlarmConfig, AlarmFlags, AlarmMatch, AlarmPolarity};
+pub use datetime::{DateTime, OutOfRange, Weekday};
+#[cfg(feature = "async")]
+pub use eeprom::EepromAsync;
+pub use eeprom::{BlockProtect, Eeprom};
+pub use error::Error;
+pub use eui::{Eui48, Eui64};
+pub use output::{MfpMode, SquareWaveFrequency};
+pub use power::{PowerFailTimestamp, PowerFailTimestamps, PowerFailWindow};
+pub use status::Status;
+pub use timezone::{InvalidTimeZone, TimeZone, Transition, TransitionDate};
+pub use trim::{Trim, TrimMode};
+pub use zoned::ZonedRtc;
+#[cfg(feature = "async")]
+pub use zoned::ZonedRtcAsync;
+
+/// Size of the battery-backed SRAM in bytes
+pub const SRAM_SIZE: usize = 64;
+
+const SRAM_START: u8 = 0x20;
+
+/// Size of the protected EEPROM block holding the EUI, on parts that have one
+pub const PROTECTED_EEPROM_SIZE: usize = 8;
+
+/// Size of the user EEPROM in bytes, on parts that have one
+pub const EEPROM_SIZE: usize = 128;
+
+/// Size of an EEPROM write page in bytes, a single write cycle can't cross a page boundary
+pub const EEPROM_PAGE_SIZE: usize = 8;
+
+/// Bytes of SRAM used by century tracking, see [`Mcp7940n::set_century_tracking`]
+pub const CENTURY_RECORD_SIZE: usize = 3;
+
+/// Reads of the time registers to attempt before giving up on getting a consistent snapshot
+const TIME_READ_ATTEMPTS: usize = 3;
+
+/// How long OSCRUN has to stay set after the oscillator starts for it to be considered stable.
+/// OSCRUN clears within TOSF (around 1 ms) of the oscillator stopping, so this leaves room for
+/// several missed cycles to show up.
+const OSCILLATOR_SETTLE_MS: u32 = 10;
+
+/// Number of times OSCRUN is polled after stopping the oscillator, each poll is a full I2C
+/// transaction so this comfortably covers TOSF even on a fast bus
+const OSCILLATOR_STOP_POLLS: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ClockSource {
     ExtCrystal,
     ExtClock,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ClockConfig {
     pub enabled: bool,
     pub clock_source: ClockSource,
 }
 
-pub struct Mcp7940n<I> {
+/// Format the device stores hours in. The driver always works in 24-hour time, this only
+/// affects what is held in the registers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HourMode {
+    TwelveHour,
+    #[default]
+    TwentyFourHour,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(Mcp7940n(async = "Mcp7940nAsync"), I2c(async = "AsyncI2c"))
+    )
+)]
+/// Driver for the MCP794xx family, see [`variant`] for the supported parts
+#[derive(Debug)]
+pub struct Mcp7940n<I, V = Mcp7940N> {
     i2c: I,
+    first_weekday: Weekday,
+    century: u8,
+    century_tracking: Option<u8>,
+    variant: PhantomData<V>,
 }
 
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(Mcp7940n(async = "Mcp7940nAsync"), I2c(async = "AsyncI2c"))
+    )
+)]
 impl<I> Mcp7940n<I> {
+    pub fn new(i2c: I) -> Self {
+        Self::with_variant(i2c, Mcp7940N)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(Mcp7940n(async = "Mcp7940nAsync"), I2c(async = "AsyncI2c"))
+    )
+)]
+impl<I, V: Variant> Mcp7940n<I, V> {
     const ADDRESS: u8 = 0b110_1111;
 
-    pub fn new(i2c: I) -> Self {
-        Self { i2c }
+    /// Creates a driver for the part given by `variant`, for example
+    /// `Mcp7940n::with_variant(i2c, variant::Mcp7940M)`
+    pub fn with_variant(i2c: I, _variant: V) -> Self {
+        Self {
+            i2c,
+            first_weekday: Weekday::Monday,
+            century: 20,
+            century_tracking: None,
+            variant: PhantomData,
+        }
+    }
+
+    /// Sets which weekday is stored as 1 in the weekday registers, defaults to Monday. The
+    /// device leaves this mapping up to the user, it only increments the value at midnight.
+    pub fn set_first_weekday(&mut self, weekday: Weekday) {
+        self.first_weekday = weekday;
+    }
+
+    pub fn first_weekday(&self) -> Weekday {
+        self.first_weekday
+    }
+
+    /// Sets the century the two digit year register counts from, defaults to 20 for years 2000
+    /// through 2099. With century tracking enabled this is overwritten by the tracked century.
+    pub fn set_century(&mut self, century: u8) {
+        self.century = century;
+    }
+
+    pub fn century(&self) -> u8 {
+        self.century
+    }
+
+    /// Persists the century in SRAM at `offset`, using [`CENTURY_RECORD_SIZE`] bytes, so it can
+    /// be carried over when the year register rolls over from 99 to 00. The device doesn't carry
+    /// into anything past the year, so the rollover is picked up the next time the time is read,
+    /// which has to happen at least once per century.
+    pub fn set_century_tracking(&mut self, offset: Option<u8>) {
+        self.century_tracking = offset;
+    }
+
+    pub fn century_tracking(&self) -> Option<u8> {
+        self.century_tracking
     }
 
     pub fn destroy(self) -> I {
@@ -29,142 +194,1113 @@ impl<I> Mcp7940n<I> {
     }
 }
 
-impl<I: I2c> Mcp7940n<I> {
-    pub fn configure_clock(&mut self, config: &ClockConfig) -> Result<(), I::Error> {
-        let mut data = [0u8; 9];
-        // Just read all the data - bit excessive since we only need 2 of these registers but lets us make sure to
-        // keep all the data synced with a single write
-        self.i2c
-            .write_read(Self::ADDRESS, &[0x00], &mut data[1..])?;
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(
+            Mcp7940n(async = "Mcp7940nAsync"),
+            I2c(async = "AsyncI2c"),
+            DelayNs(async = "AsyncDelayNs")
+        )
+    )
+)]
+impl<I: I2c, V: Variant> Mcp7940n<I, V> {
+    /// Starts or stops the oscillator and selects the clock source. Only ST and EXTOSC are
+    /// written, the time registers are left alone.
+    pub async fn configure_clock(&mut self, config: &ClockConfig) -> Result<(), Error<I::Error>> {
+        let mut data = [0u8; 8];
+        self.read_registers(RtcSec::ADDRESS, &mut data).await?;
 
-        if config.enabled {
-            data[1] |= 0b1000_0000;
-        } else {
-            data[1] &= 0b0111_1111;
+        let mut rtcsec = reg::<RtcSec>(&data);
+        let rtcmin = reg::<RtcMin>(&data);
+        let mut control = reg::<Control>(&data);
+
+        let extosc = matches!(config.clock_source, ClockSource::ExtClock);
+        if control.extosc() != extosc {
+            control.set_extosc(extosc);
+            self.write_register(control).await?;
+        }
+
+        if rtcsec.st() == config.enabled {
+            return Ok(());
         }
 
-        match config.clock_source {
-            ClockSource::ExtClock => data[8] |= 0b0000_1000,
-            ClockSource::ExtCrystal => data[8] &= 0b1111_0111,
+        // ST shares its register with the seconds, so they get written back along with it
+        rtcsec.set_st(config.enabled);
+        self.write_register(rtcsec).await?;
+
+        if !config.enabled && !extosc {
+            // If the seconds rolled over from 59 between the read and the write, the minute has
+            // already been carried and writing back 59 would put the clock a minute ahead. Once
+            // the clock has stopped we can check for that and correct it.
+            self.wait_for_oscillator_stop().await?;
+
+            let minutes: RtcMin = self.read_register().await?;
+            if minutes != rtcmin {
+                rtcsec.set_seconds(0);
+                self.write_register(rtcsec).await?;
+            }
         }
 
-        self.i2c.write(Self::ADDRESS, &data)
+        Ok(())
     }
 
-    pub fn osc_running(&mut self) -> Result<bool, I::Error> {
-        let mut data = [0u8; 1];
+    /// Enables the oscillator from `clock_source` and waits up to `timeout_ms` for OSCRUN to set.
+    /// Once it has, OSCRUN is watched for a further few milliseconds to make sure the oscillator
+    /// keeps running.
+    ///
+    /// Returns [`Error::OscillatorStartTimeout`] if OSCRUN never sets, which usually means the
+    /// crystal or clock is missing, and [`Error::OscillatorFailed`] if it sets but drops again,
+    /// which points at a marginal crystal.
+    pub async fn start_oscillator<D: DelayNs>(
+        &mut self,
+        clock_source: ClockSource,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<(), Error<I::Error>> {
+        self.configure_clock(&ClockConfig {
+            enabled: true,
+            clock_source,
+        })
+        .await?;
+
+        let mut waited_ms = 0;
+        while !self.osc_running().await? {
+            if waited_ms >= timeout_ms {
+                return Err(Error::OscillatorStartTimeout);
+            }
+
+            delay.delay_ms(1).await;
+            waited_ms += 1;
+        }
+
+        for _ in 0..OSCILLATOR_SETTLE_MS {
+            delay.delay_ms(1).await;
+
+            if !self.osc_running().await? {
+                return Err(Error::OscillatorFailed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts the oscillator from the crystal, falling back to the external clock input if the
+    /// crystal doesn't come up. Returns the clock source that ended up running.
+    pub async fn start_oscillator_with_fallback<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<ClockSource, Error<I::Error>> {
+        match self
+            .start_oscillator(ClockSource::ExtCrystal, delay, timeout_ms)
+            .await
+        {
+            Ok(()) => return Ok(ClockSource::ExtCrystal),
+            Err(Error::OscillatorStartTimeout | Error::OscillatorFailed) => {}
+            Err(err) => return Err(err),
+        }
+
+        self.start_oscillator(ClockSource::ExtClock, delay, timeout_ms)
+            .await?;
+
+        Ok(ClockSource::ExtClock)
+    }
+
+    /// Reads back the oscillator enable (ST) and clock source (EXTOSC)
+    pub async fn clock_config(&mut self) -> Result<ClockConfig, Error<I::Error>> {
+        let mut data = [0u8; 8];
+        self.read_registers(RtcSec::ADDRESS, &mut data).await?;
+
+        Ok(decode_clock_config(&data))
+    }
+
+    /// Reads the oscillator, power-fail, MFP and alarm state in a single transaction
+    pub async fn status(&mut self) -> Result<Status, Error<I::Error>> {
+        // RTCSEC through ALM1WKDAY
+        let mut data = [0u8; 0x15];
+        self.read_registers(RtcSec::ADDRESS, &mut data).await?;
+
+        let rtcwkday = reg::<RtcWkday>(&data);
+        let control = reg::<Control>(&data);
+        let alm0wkday = AlmWkday::from_bits(data[AlmWkday::address(Alarm::Zero) as usize]);
+        let alm1wkday = AlmWkday::from_bits(data[AlmWkday::address(Alarm::One) as usize]);
+
+        Ok(Status {
+            clock: decode_clock_config(&data),
+            oscillator_running: rtcwkday.oscrun(),
+            power_failed: rtcwkday.pwrfail(),
+            battery_backup: rtcwkday.vbaten(),
+            square_wave: control.sqwen(),
+            alarm0_enabled: control.alm0en(),
+            alarm1_enabled: control.alm1en(),
+            fired_alarms: AlarmFlags {
+                alarm0: alm0wkday.almif(),
+                alarm1: alm1wkday.almif(),
+            },
+        })
+    }
+
+    pub async fn osc_running(&mut self) -> Result<bool, Error<I::Error>> {
+        let rtcwkday: RtcWkday = self.read_register().await?;
+
+        Ok(rtcwkday.oscrun())
+    }
+
+    /// Reads the current date and time. Returns [`Error::OscillatorNotRunning`] if the oscillator
+    /// is disabled, as the time registers are then not advancing. Use [`Self::osc_running`] to
+    /// check that an enabled oscillator is actually running.
+    ///
+    /// Returns [`Error::LeapYearMismatch`] if LPYR doesn't agree with the year register, which
+    /// means the registers are corrupt.
+    ///
+    /// The device treats every year divisible by 4 as a leap year, so in century years that
+    /// aren't, such as 2100, it counts a February 29. That day is read as March 1, and the
+    /// registers are corrected to March 1 when it is read. If the time isn't read at all on that
+    /// day the device stays a day behind until the time is set again.
+    pub async fn now(&mut self) -> Result<DateTime, Error<I::Error>> {
+        let data = self.read_time_registers().await?;
+
+        // OSCRUN drops for as long as the oscillator takes to restart whenever the time is set,
+        // so go by whether it is enabled instead
+        if !reg::<RtcSec>(&data).st() && !reg::<Control>(&data).extosc() {
+            return Err(Error::OscillatorNotRunning);
+        }
+
+        self.update_century(bcd(reg::<RtcYear>(&data).year())?)
+            .await?;
+
+        let datetime = decode_datetime(&data, self.century)?;
+
+        // Move the device on from a February 29 that doesn't exist, otherwise it would count
+        // March 1 a day late
+        let day = bcd(reg::<RtcDate>(&data).day())?;
+        let month = bcd(reg::<RtcMth>(&data).month())?;
+        if is_skipped_leap_day(datetime.year(), month, day) {
+            let mut rtcdate = RtcDate::default();
+            rtcdate.set_day(1);
+            let mut rtcmth = RtcMth::default();
+            rtcmth.set_month(3);
+
+            self.write_time_registers(&[RtcDate::ADDRESS, rtcdate.bits(), rtcmth.bits()])
+                .await?;
+        }
+
+        Ok(datetime)
+    }
+
+    /// Reads the time as seconds since 1970-01-01 00:00:00
+    pub async fn now_unix(&mut self) -> Result<i64, Error<I::Error>> {
+        Ok(self.now().await?.unix_timestamp())
+    }
+
+    /// Sets the time from seconds since 1970-01-01 00:00:00, see [`Self::set_datetime`]
+    pub async fn set_unix(&mut self, timestamp: i64) -> Result<(), Error<I::Error>> {
+        let datetime = DateTime::from_unix_timestamp(timestamp).ok_or(Error::YearOutOfRange)?;
+
+        self.set_datetime(datetime).await
+    }
+
+    /// Moves the clock forward by `duration`, carrying into the date as needed. Fractions of a
+    /// second are dropped.
+    pub async fn adjust_by(&mut self, duration: Duration) -> Result<(), Error<I::Error>> {
+        let seconds = i64::try_from(duration.as_secs()).map_err(|_| Error::YearOutOfRange)?;
 
-        self.i2c.write_read(Self::ADDRESS, &[0x03], &mut data)?;
+        self.adjust_by_seconds(seconds).await
+    }
+
+    /// Moves the clock back by `duration`, see [`Self::adjust_by`]
+    pub async fn adjust_back_by(&mut self, duration: Duration) -> Result<(), Error<I::Error>> {
+        let seconds = i64::try_from(duration.as_secs()).map_err(|_| Error::YearOutOfRange)?;
+
+        self.adjust_by_seconds(-seconds).await
+    }
+
+    /// Reads the weekday from RTCWKDAY
+    pub async fn weekday(&mut self) -> Result<Weekday, Error<I::Error>> {
+        let rtcwkday: RtcWkday = self.read_register().await?;
+
+        decode_weekday(rtcwkday.weekday(), self.first_weekday)
+    }
+
+    /// Checks that the weekday stored on the device is the weekday of the stored date, which is
+    /// not the case if the date was ever written without the matching weekday
+    pub async fn weekday_matches_date(&mut self) -> Result<bool, Error<I::Error>> {
+        let data = self.read_time_registers().await?;
+
+        self.update_century(bcd(reg::<RtcYear>(&data).year())?)
+            .await?;
+
+        let datetime = decode_datetime(&data, self.century)?;
+        let weekday = decode_weekday(reg::<RtcWkday>(&data).weekday(), self.first_weekday)?;
+
+        Ok(datetime.weekday() == weekday)
+    }
+
+    /// Sets the date and time, along with the matching weekday. The hour is stored in the
+    /// currently configured [`HourMode`]. The device clears PWRFAIL on any write to RTCWKDAY, so
+    /// check [`Self::power_failed`] before calling this if the power-fail state matters.
+    ///
+    /// The clock is stopped while the new time is loaded so nothing can roll over halfway
+    /// through, and is restarted afterwards if it was running.
+    ///
+    /// Returns [`Error::YearOutOfRange`] if the year isn't within the configured century, or with
+    /// century tracking enabled if it is before year 0 or past what the tracked century can
+    /// hold. Dates in century years that aren't leap years, such as 2100, are accepted, see
+    /// [`Self::now`] for how the leap day the device adds to them is handled.
+    ///
+    /// Accepts anything that converts into a [`DateTime`], such as the date and time types of
+    /// the enabled date libraries.
+    pub async fn set_datetime<D: TryInto<DateTime>>(
+        &mut self,
+        datetime: D,
+    ) -> Result<(), Error<I::Error>> {
+        let datetime = datetime.try_into().map_err(|_| Error::YearOutOfRange)?;
 
-        Ok(data[0] & 0b0010_0000 != 0)
+        self.write_datetime(datetime, self.first_weekday).await
     }
 
-    pub fn now(&mut self) -> Result<NaiveDateTime, I::Error> {
+    /// Sets the date and time as [`Self::set_datetime`] does, storing the weekday numbered from
+    /// `first_weekday`
+    pub(crate) async fn write_datetime(
+        &mut self,
+        datetime: DateTime,
+        first_weekday: Weekday,
+    ) -> Result<(), Error<I::Error>> {
+        let (century, year) = self.split_year(datetime.year())?;
+
         let mut data = [0u8; 7];
-        self.i2c.write_read(Self::ADDRESS, &[0x00], &mut data)?;
+        self.read_registers(RtcSec::ADDRESS, &mut data).await?;
 
-        let sec_ten = (data[0] & 0b0111_0000) >> 4;
-        let sec_ones = data[0] & 0b0000_1111;
+        // Only VBATEN is carried over, everything else is either part of the time, read only or
+        // cleared by the write anyway
+        let mut rtcsec = RtcSec::default();
+        rtcsec.set_seconds(datetime.second());
 
-        let secs = (sec_ten * 10) + sec_ones;
+        let mut rtcmin = RtcMin::default();
+        rtcmin.set_minutes(datetime.minute());
 
-        let min_ten = (data[1] & 0b0111_0000) >> 4;
-        let min_ones = data[1] & 0b0000_1111;
+        // Keep whichever hour format the device is currently configured for
+        let mut rtchour = RtcHour::default();
+        rtchour.set_hour(datetime.hour(), reg::<RtcHour>(&data).twelve_hour());
 
-        let min = (min_ten * 10) + min_ones;
+        let mut rtcwkday = RtcWkday::default();
+        rtcwkday.set_vbaten(reg::<RtcWkday>(&data).vbaten());
+        rtcwkday.set_weekday(encode_weekday(datetime.weekday(), first_weekday));
 
-        let hr_12 = (data[2] & 0b0100_0000) != 0;
-        let hr_ones = data[2] & 0b0000_1111;
+        let mut rtcdate = RtcDate::default();
+        rtcdate.set_day(datetime.day());
 
-        // We want to always convert to 24hr time
-        let hour = if hr_12 {
-            let pm = (data[2] & 0b0010_0000) != 0;
-            let hr_ten = (data[2] & 0b0001_0000) >> 4;
-            let hr = (hr_ten * 10) + hr_ones;
+        let mut rtcmth = RtcMth::default();
+        rtcmth.set_month(datetime.month());
 
-            if pm && hr != 12 {
-                hr + 12
-            } else if !pm && hr == 12 {
-                0
-            } else {
-                hr
-            }
+        let mut rtcyear = RtcYear::default();
+        rtcyear.set_year(year);
+
+        let data = [
+            RtcSec::ADDRESS,
+            rtcsec.bits(),
+            rtcmin.bits(),
+            rtchour.bits(),
+            rtcwkday.bits(),
+            rtcdate.bits(),
+            rtcmth.bits(),
+            rtcyear.bits(),
+        ];
+        self.write_time_registers(&data).await?;
+
+        self.store_century(century, year).await
+    }
+
+    pub async fn hour_mode(&mut self) -> Result<HourMode, Error<I::Error>> {
+        let rtchour: RtcHour = self.read_register().await?;
+
+        if rtchour.twelve_hour() {
+            Ok(HourMode::TwelveHour)
+        } else {
+            Ok(HourMode::TwentyFourHour)
+        }
+    }
+
+    /// Switches the format hours are stored in, converting the current hour and both alarm hours
+    /// so they keep their meaning
+    pub async fn set_hour_mode(&mut self, mode: HourMode) -> Result<(), Error<I::Error>> {
+        let twelve_hour = mode == HourMode::TwelveHour;
+
+        let rtchour: RtcHour = self.read_register().await?;
+        if rtchour.twelve_hour() == twelve_hour {
+            return Ok(());
+        }
+
+        // The 12/24 bit of ALMxHOUR mirrors RTCHOUR, so the alarm hours have to be decoded
+        // before RTCHOUR changes format
+        let mut alm0hour: AlmHour = self.read_alarm_register(Alarm::Zero).await?;
+        let mut alm1hour: AlmHour = self.read_alarm_register(Alarm::One).await?;
+
+        // Convert the hour with the clock stopped, so it can't roll over between the read and
+        // the write
+        let running = self.stop_clock().await?;
+        let mut rtchour: RtcHour = self.read_register().await?;
+        let hour = bcd(rtchour.hour())?;
+        rtchour.set_hour(hour, twelve_hour);
+        self.write_register(rtchour).await?;
+        self.restart_clock(running).await?;
+
+        // An alarm that was never configured may not hold a valid hour, there is nothing to
+        // preserve in that case
+        if let Some(hour) = alm0hour.hour() {
+            alm0hour.set_hour(hour, twelve_hour);
+            self.write_alarm_register(Alarm::Zero, alm0hour).await?;
+        }
+
+        if let Some(hour) = alm1hour.hour() {
+            alm1hour.set_hour(hour, twelve_hour);
+            self.write_alarm_register(Alarm::One, alm1hour).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_alarm(
+        &mut self,
+        alarm: Alarm,
+        config: &AlarmConfig,
+    ) -> Result<(), Error<I::Error>> {
+        if !config.is_valid() {
+            return Err(Error::InvalidDate);
+        }
+
+        // The alarm hour has to follow the 12/24 format of RTCHOUR
+        let rtchour: RtcHour = self.read_register().await?;
+
+        // ALMPOL only lives in ALM0WKDAY, so make sure we carry it over
+        let current: AlmWkday = self.read_alarm_register(alarm).await?;
+
+        let mut almsec = AlmSec::default();
+        almsec.set_seconds(config.second);
+
+        let mut almmin = AlmMin::default();
+        almmin.set_minutes(config.minute);
+
+        let mut almhour = AlmHour::default();
+        almhour.set_hour(config.hour, rtchour.twelve_hour());
+
+        // Writing ALMxWKDAY always clears ALMxIF
+        let mut almwkday = AlmWkday::default();
+        almwkday.set_almpol(current.almpol());
+        almwkday.set_almmsk(config.matches.bits());
+        almwkday.set_weekday(encode_weekday(config.weekday, self.first_weekday));
+
+        let mut almdate = AlmDate::default();
+        almdate.set_day(config.day);
+
+        let mut almmth = AlmMth::default();
+        almmth.set_month(config.month);
+
+        let data = [
+            AlmSec::address(alarm),
+            almsec.bits(),
+            almmin.bits(),
+            almhour.bits(),
+            almwkday.bits(),
+            almdate.bits(),
+            almmth.bits(),
+        ];
+
+        self.write_registers(&data).await
+    }
+
+    /// Reads back the alarm configuration. Returns [`Error::InvalidDate`] if the registers hold a
+    /// value outside the ranges [`Self::set_alarm`] accepts.
+    pub async fn alarm(&mut self, alarm: Alarm) -> Result<AlarmConfig, Error<I::Error>> {
+        let mut data = [0u8; 6];
+        self.read_registers(AlmSec::address(alarm), &mut data)
+            .await?;
+
+        let almwkday = alarm_reg::<AlmWkday>(&data);
+
+        let config = AlarmConfig {
+            matches: AlarmMatch::from_bits(almwkday.almmsk()).ok_or(Error::InvalidAlarmMask)?,
+            hour: bcd(alarm_reg::<AlmHour>(&data).hour())?,
+            minute: bcd(alarm_reg::<AlmMin>(&data).minutes())?,
+            second: bcd(alarm_reg::<AlmSec>(&data).seconds())?,
+            weekday: decode_weekday(almwkday.weekday(), self.first_weekday)?,
+            day: bcd(alarm_reg::<AlmDate>(&data).day())?,
+            month: bcd(alarm_reg::<AlmMth>(&data).month())?,
+        };
+
+        if !config.is_valid() {
+            return Err(Error::InvalidDate);
+        }
+
+        Ok(config)
+    }
+
+    pub async fn enable_alarm(&mut self, alarm: Alarm) -> Result<(), Error<I::Error>> {
+        let mut control: Control = self.read_register().await?;
+        control.set_alarm_enabled(alarm, true);
+
+        self.write_register(control).await
+    }
+
+    pub async fn disable_alarm(&mut self, alarm: Alarm) -> Result<(), Error<I::Error>> {
+        let mut control: Control = self.read_register().await?;
+        control.set_alarm_enabled(alarm, false);
+
+        self.write_register(control).await
+    }
+
+    pub async fn alarm_enabled(&mut self, alarm: Alarm) -> Result<bool, Error<I::Error>> {
+        let control: Control = self.read_register().await?;
+
+        Ok(control.alarm_enabled(alarm))
+    }
+
+    /// Sets the MFP polarity used by both alarms. This also clears the Alarm 0 interrupt flag,
+    /// as the device clears ALM0IF on any write to ALM0WKDAY.
+    pub async fn set_alarm_polarity(
+        &mut self,
+        polarity: AlarmPolarity,
+    ) -> Result<(), Error<I::Error>> {
+        let mut almwkday: AlmWkday = self.read_alarm_register(Alarm::Zero).await?;
+        almwkday.set_almpol(polarity == AlarmPolarity::ActiveHigh);
+
+        self.write_alarm_register(Alarm::Zero, almwkday).await
+    }
+
+    pub async fn alarm_polarity(&mut self) -> Result<AlarmPolarity, Error<I::Error>> {
+        let almwkday: AlmWkday = self.read_alarm_register(Alarm::Zero).await?;
+
+        if almwkday.almpol() {
+            Ok(AlarmPolarity::ActiveHigh)
+        } else {
+            Ok(AlarmPolarity::ActiveLow)
+        }
+    }
+
+    pub async fn alarm_fired(&mut self, alarm: Alarm) -> Result<bool, Error<I::Error>> {
+        let almwkday: AlmWkday = self.read_alarm_register(alarm).await?;
+
+        Ok(almwkday.almif())
+    }
+
+    /// Reads the interrupt flags of both alarms in a single transaction
+    pub async fn fired_alarms(&mut self) -> Result<AlarmFlags, Error<I::Error>> {
+        let (alm0wkday, alm1wkday) = self.read_alarm_wkdays().await?;
+
+        Ok(AlarmFlags {
+            alarm0: alm0wkday.almif(),
+            alarm1: alm1wkday.almif(),
+        })
+    }
+
+    /// Clears the interrupt flag of `alarm`. If the alarm condition still matches the device will
+    /// immediately set the flag again.
+    pub async fn clear_alarm_flag(&mut self, alarm: Alarm) -> Result<(), Error<I::Error>> {
+        let mut almwkday: AlmWkday = self.read_alarm_register(alarm).await?;
+        almwkday.set_almif(false);
+
+        self.write_alarm_register(alarm, almwkday).await
+    }
+
+    pub async fn clear_alarm_flags(&mut self) -> Result<(), Error<I::Error>> {
+        self.clear_alarm_flag(Alarm::Zero).await?;
+        self.clear_alarm_flag(Alarm::One).await
+    }
+
+    /// Reads the interrupt flags of both alarms and clears only the ones that were found set,
+    /// returning which alarms fired.
+    ///
+    /// Alarms whose flag was clear are left untouched, so a flag that gets set by the device
+    /// after the read is never lost.
+    pub async fn acknowledge_alarms(&mut self) -> Result<AlarmFlags, Error<I::Error>> {
+        let (mut alm0wkday, mut alm1wkday) = self.read_alarm_wkdays().await?;
+
+        let flags = AlarmFlags {
+            alarm0: alm0wkday.almif(),
+            alarm1: alm1wkday.almif(),
+        };
+
+        if flags.alarm0 {
+            alm0wkday.set_almif(false);
+            self.write_alarm_register(Alarm::Zero, alm0wkday).await?;
+        }
+
+        if flags.alarm1 {
+            alm1wkday.set_almif(false);
+            self.write_alarm_register(Alarm::One, alm1wkday).await?;
+        }
+
+        Ok(flags)
+    }
+
+    /// Sets the digital trim, rounding to the nearest step the hardware supports. Returns
+    /// [`Error::TrimOutOfRange`] if the trim exceeds 127 steps in the requested mode.
+    ///
+    /// Coarse trim drives the MFP with a 64 Hz output, so it returns [`Error::MfpConflict`]
+    /// while the square wave output is enabled.
+    pub async fn set_trim(&mut self, trim: &Trim) -> Result<(), Error<I::Error>> {
+        let osctrim = trim.to_register().ok_or(Error::TrimOutOfRange)?;
+
+        let mut control: Control = self.read_register().await?;
+        if trim.mode == TrimMode::Coarse && control.sqwen() {
+            return Err(Error::MfpConflict);
+        }
+
+        control.set_crstrim(trim.mode == TrimMode::Coarse);
+
+        self.write_registers(&[Control::ADDRESS, control.bits(), osctrim.bits()])
+            .await
+    }
+
+    pub async fn trim(&mut self) -> Result<Trim, Error<I::Error>> {
+        let mut data = [0u8; 2];
+        self.read_registers(Control::ADDRESS, &mut data).await?;
+
+        let mode = if Control::from_bits(data[0]).crstrim() {
+            TrimMode::Coarse
         } else {
-            let hr_ten = (data[2] & 0b0011_0000) >> 4;
-            (hr_ten * 10) + hr_ones
+            TrimMode::Fine
         };
 
-        let day_ten = (data[4] & 0b0011_0000) >> 4;
-        let day_ones = data[4] & 0b0000_1111;
+        Ok(Trim::from_register(OscTrim::from_bits(data[1]), mode))
+    }
+
+    /// Reads `data.len()` bytes of SRAM starting at `offset`
+    pub async fn read_sram(&mut self, offset: u8, data: &mut [u8]) -> Result<(), Error<I::Error>> {
+        if offset as usize + data.len() > SRAM_SIZE {
+            return Err(Error::SramOutOfBounds);
+        }
+
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.read_registers(SRAM_START + offset, data).await
+    }
+
+    /// Writes `data` to SRAM starting at `offset`
+    pub async fn write_sram(&mut self, offset: u8, data: &[u8]) -> Result<(), Error<I::Error>> {
+        if offset as usize + data.len() > SRAM_SIZE {
+            return Err(Error::SramOutOfBounds);
+        }
+
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut buf = [0u8; SRAM_SIZE + 1];
+        buf[0] = SRAM_START + offset;
+        buf[1..=data.len()].copy_from_slice(data);
+
+        self.write_registers(&buf[..=data.len()]).await
+    }
+
+    /// Sets every byte of SRAM to `value`
+    pub async fn fill_sram(&mut self, value: u8) -> Result<(), Error<I::Error>> {
+        let mut buf = [value; SRAM_SIZE + 1];
+        buf[0] = SRAM_START;
+
+        self.write_registers(&buf).await
+    }
+
+    pub async fn clear_sram(&mut self) -> Result<(), Error<I::Error>> {
+        self.fill_sram(0).await
+    }
+
+    /// Configures the MFP pin. Returns [`Error::MfpConflict`] if the device's output precedence
+    /// rules would prevent `mode` from taking effect: a general purpose output while an alarm is
+    /// enabled, an alarm interrupt output with no alarm enabled, or a square wave while coarse
+    /// trim mode replaces it with the 64 Hz trim clock.
+    pub async fn set_mfp_mode(&mut self, mode: MfpMode) -> Result<(), Error<I::Error>> {
+        let mut control: Control = self.read_register().await?;
+
+        let alarms_enabled = control.alm0en() || control.alm1en();
+
+        match mode {
+            MfpMode::SquareWave(frequency) => {
+                if control.crstrim() {
+                    return Err(Error::MfpConflict);
+                }
+
+                control.set_sqwen(true);
+                control.set_sqwfs(frequency.bits());
+            }
+            MfpMode::GeneralPurpose(level) => {
+                if alarms_enabled {
+                    return Err(Error::MfpConflict);
+                }
+
+                control.set_sqwen(false);
+                control.set_out(level);
+            }
+            MfpMode::AlarmInterrupt => {
+                if !alarms_enabled {
+                    return Err(Error::MfpConflict);
+                }
+
+                control.set_sqwen(false);
+            }
+        }
+
+        self.write_register(control).await
+    }
+
+    pub async fn mfp_mode(&mut self) -> Result<MfpMode, Error<I::Error>> {
+        let control: Control = self.read_register().await?;
+
+        if control.sqwen() {
+            Ok(MfpMode::SquareWave(SquareWaveFrequency::from_bits(
+                control.sqwfs(),
+            )))
+        } else if control.alm0en() || control.alm1en() {
+            Ok(MfpMode::AlarmInterrupt)
+        } else {
+            Ok(MfpMode::GeneralPurpose(control.out()))
+        }
+    }
+
+    /// Reads and decodes every register from RTCSEC through PWRUPMTH in one transaction, for
+    /// logging or debugging
+    pub async fn register_dump(&mut self) -> Result<RegisterDump, Error<I::Error>> {
+        let mut data = [0u8; REGISTER_DUMP_SIZE];
+        self.read_registers(RtcSec::ADDRESS, &mut data).await?;
+
+        Ok(RegisterDump::from_bytes(&data))
+    }
+
+    /// Reads a single register, see [`registers`] for the available types
+    pub async fn read_register<R: Register>(&mut self) -> Result<R, Error<I::Error>> {
+        let mut data = [0u8; 1];
+        self.read_registers(R::ADDRESS, &mut data).await?;
+
+        Ok(R::from_bits(data[0]))
+    }
+
+    /// Writes a single register. Writing a register rewrites every field in it, including ones
+    /// with side effects such as clearing PWRFAIL or ALMxIF.
+    pub async fn write_register<R: Register>(
+        &mut self,
+        register: R,
+    ) -> Result<(), Error<I::Error>> {
+        self.write_registers(&[R::ADDRESS, register.bits()]).await
+    }
+
+    /// Reads a single register of `alarm`
+    pub async fn read_alarm_register<R: AlarmRegister>(
+        &mut self,
+        alarm: Alarm,
+    ) -> Result<R, Error<I::Error>> {
+        let mut data = [0u8; 1];
+        self.read_registers(R::address(alarm), &mut data).await?;
+
+        Ok(R::from_bits(data[0]))
+    }
+
+    /// Writes a single register of `alarm`
+    pub async fn write_alarm_register<R: AlarmRegister>(
+        &mut self,
+        alarm: Alarm,
+        register: R,
+    ) -> Result<(), Error<I::Error>> {
+        self.write_registers(&[R::address(alarm), register.bits()])
+            .await
+    }
+
+    /// Reads a single register of the `event` timestamp
+    pub async fn read_power_fail_register<R: PowerFailRegister>(
+        &mut self,
+        event: PowerFailEvent,
+    ) -> Result<R, Error<I::Error>> {
+        let mut data = [0u8; 1];
+        self.read_registers(R::address(event), &mut data).await?;
+
+        Ok(R::from_bits(data[0]))
+    }
+
+    /// Splits a year into the century and the year within it, checking that the device can hold
+    /// it
+    fn split_year(&self, year: u16) -> Result<(u8, u8), Error<I::Error>> {
+        let century = (year / 100) as u8;
+        let year_in_century = (year % 100) as u8;
+
+        if self.century_tracking.is_none() && century != self.century {
+            return Err(Error::YearOutOfRange);
+        }
+
+        Ok((century, year_in_century))
+    }
+
+    /// Records the century of a newly written year, persisting it if century tracking is enabled
+    async fn store_century(&mut self, century: u8, year: u8) -> Result<(), Error<I::Error>> {
+        if let Some(offset) = self.century_tracking {
+            self.write_sram(offset, &encode_century_record(century, year))
+                .await?;
+        }
+        self.century = century;
+
+        Ok(())
+    }
+
+    /// Writes `data` to the time registers, the first byte being the register address to start
+    /// at. As recommended by the datasheet the clock is stopped while the registers are loaded so
+    /// nothing can roll over halfway through, and is restarted afterwards if it was running. Any
+    /// RTCSEC value in `data` must have ST clear.
+    async fn write_time_registers(&mut self, data: &[u8]) -> Result<(), Error<I::Error>> {
+        let running = self.stop_clock().await?;
+
+        self.write_registers(data).await?;
+
+        self.restart_clock(running).await
+    }
+
+    /// Stops the clock from whichever source drives it and waits for OSCRUN to clear, returning
+    /// RTCSEC and CONTROL as they were so [`Self::restart_clock`] can restore them
+    async fn stop_clock(&mut self) -> Result<(RtcSec, Control), Error<I::Error>> {
+        let mut current = [0u8; 8];
+        self.read_registers(RtcSec::ADDRESS, &mut current).await?;
+
+        let rtcsec = reg::<RtcSec>(&current);
+        let control = reg::<Control>(&current);
 
-        let day = (day_ten * 10) + day_ones;
+        if rtcsec.st() {
+            let mut rtcsec = rtcsec;
+            rtcsec.set_st(false);
+            self.write_register(rtcsec).await?;
+        }
+
+        if control.extosc() {
+            let mut control = control;
+            control.set_extosc(false);
+            self.write_register(control).await?;
+        }
+
+        self.wait_for_oscillator_stop().await?;
+
+        Ok((rtcsec, control))
+    }
+
+    /// Restarts the clock if it was running before [`Self::stop_clock`]
+    async fn restart_clock(
+        &mut self,
+        (rtcsec, control): (RtcSec, Control),
+    ) -> Result<(), Error<I::Error>> {
+        // The seconds are static while the clock is stopped so writing them back is safe
+        if rtcsec.st() {
+            let mut rtcsec: RtcSec = self.read_register().await?;
+            rtcsec.set_st(true);
+            self.write_register(rtcsec).await?;
+        }
+
+        if control.extosc() {
+            self.write_register(control).await?;
+        }
+
+        Ok(())
+    }
 
-        let month_ten = (data[5] & 0b0001_0000) >> 4;
-        let month_ones = data[5] & 0b0000_1111;
+    /// Brings the century up to date with the tracking record in SRAM, advancing it if the year
+    /// register has rolled over since the record was last written. Does nothing if century
+    /// tracking is disabled.
+    async fn update_century(&mut self, year: u8) -> Result<(), Error<I::Error>> {
+        let offset = match self.century_tracking {
+            Some(offset) => offset,
+            None => return Ok(()),
+        };
 
-        let month = (month_ten * 10) + month_ones;
+        let mut record = [0u8; CENTURY_RECORD_SIZE];
+        self.read_sram(offset, &mut record).await?;
 
-        let year_ten = (data[6] & 0b1111_0000) >> 4;
-        let year_ones = data[6] & 0b0000_1111;
+        // A record that doesn't check out was never written, so start tracking from the
+        // configured century
+        let century = match decode_century_record(&record) {
+            Some((century, last_year)) if year < last_year => {
+                century.checked_add(1).ok_or(Error::YearOutOfRange)?
+            }
+            Some((century, last_year)) if year == last_year => {
+                self.century = century;
+                return Ok(());
+            }
+            Some((century, _)) => century,
+            None => self.century,
+        };
 
-        let year = (year_ten * 10) as i32 + year_ones as i32 + 2000;
+        self.write_sram(offset, &encode_century_record(century, year))
+            .await?;
+        self.century = century;
 
-        let date = NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap();
-        Ok(date
-            .and_hms_opt(hour as u32, min as u32, secs as u32)
-            .unwrap())
+        Ok(())
     }
 
-    pub fn set_datetime(&mut self, now: &NaiveDateTime) -> Result<(), I::Error> {
-        let time = now.time();
-        let date = now.date();
+    async fn adjust_by_seconds(&mut self, seconds: i64) -> Result<(), Error<I::Error>> {
+        let datetime = self
+            .now()
+            .await?
+            .checked_add_seconds(seconds)
+            .ok_or(Error::YearOutOfRange)?;
 
-        let seconds_tens = (time.second() / 10) as u8;
-        let seconds_ones = (time.second() % 10) as u8;
+        self.set_datetime(datetime).await
+    }
 
-        let minutes_tens = (time.minute() / 10) as u8;
-        let minutes_ones = (time.minute() % 10) as u8;
+    /// Reads RTCSEC through CONTROL. The device doesn't latch the time registers while they are
+    /// being read, so the block is read until two consecutive reads agree to rule out a rollover
+    /// partway through.
+    async fn read_time_registers(&mut self) -> Result<[u8; 8], Error<I::Error>> {
+        let mut previous = [0u8; 8];
+        self.read_registers(RtcSec::ADDRESS, &mut previous).await?;
 
-        let hours_tens = (time.hour() / 10) as u8;
-        let hours_ones = (time.hour() % 10) as u8;
+        for _ in 0..TIME_READ_ATTEMPTS {
+            let mut data = [0u8; 8];
+            self.read_registers(RtcSec::ADDRESS, &mut data).await?;
 
-        let day_tens = (date.day() / 10) as u8;
-        let day_ones = (date.day() % 10) as u8;
+            if data == previous {
+                return Ok(data);
+            }
 
-        let month_tens = (date.month() / 10) as u8;
-        let month_ones = (date.month() % 10) as u8;
+            previous = data;
+        }
 
-        let year_tens = ((date.year() - 2000) / 10) as u8;
-        let year_ones = ((date.year() - 2000) % 10) as u8;
+        Err(Error::InconsistentRead)
+    }
 
+    /// Polls OSCRUN until the device reports the oscillator has stopped, which takes up to TOSF
+    /// (around 1 ms) after ST or EXTOSC is cleared
+    async fn wait_for_oscillator_stop(&mut self) -> Result<(), Error<I::Error>> {
+        for _ in 0..OSCILLATOR_STOP_POLLS {
+            let rtcwkday: RtcWkday = self.read_register().await?;
+
+            if !rtcwkday.oscrun() {
+                return Ok(());
+            }
+        }
+
+        Err(Error::OscillatorStopTimeout)
+    }
+
+    /// Reads ALM0WKDAY through ALM1WKDAY in one transaction
+    async fn read_alarm_wkdays(&mut self) -> Result<(AlmWkday, AlmWkday), Error<I::Error>> {
         let mut data = [0u8; 8];
+        self.read_registers(AlmWkday::address(Alarm::Zero), &mut data)
+            .await?;
+
+        let alarm1 = AlmWkday::address(Alarm::One) - AlmWkday::address(Alarm::Zero);
+
+        Ok((
+            AlmWkday::from_bits(data[0]),
+            AlmWkday::from_bits(data[alarm1 as usize]),
+        ))
+    }
+
+    async fn read_registers(&mut self, reg: u8, data: &mut [u8]) -> Result<(), Error<I::Error>> {
         self.i2c
-            .write_read(Self::ADDRESS, &[0x00], &mut data[1..])?;
+            .write_read(Self::ADDRESS, &[reg], data)
+            .await
+            .map_err(Error::Bus)
+    }
 
-        data[1] &= 0b1000_0000;
-        data[1] |= seconds_tens << 4;
-        data[1] |= seconds_ones;
+    /// Writes `data` to the device, the first byte being the register address to start at
+    async fn write_registers(&mut self, data: &[u8]) -> Result<(), Error<I::Error>> {
+        self.i2c
+            .write(Self::ADDRESS, data)
+            .await
+            .map_err(Error::Bus)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(Mcp7940n(async = "Mcp7940nAsync"), I2c(async = "AsyncI2c"))
+    )
+)]
+impl<I: I2c, V: BatteryBackup> Mcp7940n<I, V> {
+    /// Enables or disables switching over to the VBAT supply when main power is lost. This also
+    /// clears PWRFAIL, as the device clears it on any write to RTCWKDAY.
+    pub async fn set_battery_backup(&mut self, enabled: bool) -> Result<(), Error<I::Error>> {
+        let mut rtcwkday: RtcWkday = self.read_register().await?;
+        rtcwkday.set_vbaten(enabled);
 
-        data[2] &= 0b1000_0000;
-        data[2] |= minutes_tens << 4;
-        data[2] |= minutes_ones;
+        self.write_register(rtcwkday).await
+    }
 
-        data[3] &= 0b1000_0000;
-        data[3] |= hours_tens << 4;
-        data[3] |= hours_ones;
+    pub async fn battery_backup_enabled(&mut self) -> Result<bool, Error<I::Error>> {
+        let rtcwkday: RtcWkday = self.read_register().await?;
+
+        Ok(rtcwkday.vbaten())
+    }
 
-        data[5] &= 0b1100_0000;
-        data[5] |= day_tens << 4;
-        data[5] |= day_ones;
+    /// Whether main power was lost while running from VBAT. The flag stays latched, and the
+    /// power-fail timestamps stay frozen, until [`Self::clear_power_fail`] is called.
+    pub async fn power_failed(&mut self) -> Result<bool, Error<I::Error>> {
+        let rtcwkday: RtcWkday = self.read_register().await?;
 
-        data[6] &= 0b1100_0000;
-        data[6] |= month_tens << 4;
-        data[6] |= month_ones;
+        Ok(rtcwkday.pwrfail())
+    }
 
-        data[7] = 0;
-        data[7] |= year_tens << 4;
-        data[7] |= year_ones;
+    /// Clears PWRFAIL, which also resets the power-fail timestamps and re-arms them for the next
+    /// power failure
+    pub async fn clear_power_fail(&mut self) -> Result<(), Error<I::Error>> {
+        let mut rtcwkday: RtcWkday = self.read_register().await?;
+        rtcwkday.set_pwrfail(false);
 
-        self.i2c.write(Self::ADDRESS, &data)
+        self.write_register(rtcwkday).await
     }
+
+    /// Reads the power-down and power-up timestamps, or `None` if no power failure has been
+    /// latched since PWRFAIL was last cleared
+    pub async fn power_fail_timestamps(
+        &mut self,
+    ) -> Result<Option<PowerFailTimestamps>, Error<I::Error>> {
+        // Read RTCWKDAY through PWRUPMTH so the flag and timestamps come from one transaction
+        let mut data = [0u8; 29];
+        self.read_registers(RtcWkday::ADDRESS, &mut data).await?;
+
+        if !RtcWkday::from_bits(data[0]).pwrfail() {
+            return Ok(None);
+        }
+
+        let power_down = PowerFailEvent::Down.base_register() - RtcWkday::ADDRESS;
+        let power_up = PowerFailEvent::Up.base_register() - RtcWkday::ADDRESS;
+
+        Ok(Some(PowerFailTimestamps {
+            power_down: decode_power_fail_timestamp(
+                &data[power_down as usize..],
+                self.first_weekday,
+            )?,
+            power_up: decode_power_fail_timestamp(&data[power_up as usize..], self.first_weekday)?,
+        }))
+    }
+
+    /// Reads the power-fail timestamps and resolves their years against the current RTC time, or
+    /// `None` if no power failure has been latched
+    pub async fn power_fail_window(&mut self) -> Result<Option<PowerFailWindow>, Error<I::Error>> {
+        let timestamps = match self.power_fail_timestamps().await? {
+            Some(timestamps) => timestamps,
+            None => return Ok(None),
+        };
+        let now = self.now().await?;
+
+        timestamps
+            .infer_years(&now)
+            .map(Some)
+            .ok_or(Error::InvalidDate)
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(
+            Mcp7940n(async = "Mcp7940nAsync"),
+            Eeprom(async = "EepromAsync"),
+            I2c(async = "AsyncI2c")
+        )
+    )
+)]
+impl<I: I2c, V: ProtectedEeprom> Mcp7940n<I, V> {
+    /// Borrows the bus to access the EEPROM, which answers on its own I2C address
+    pub fn eeprom(&mut self) -> Eeprom<'_, I, V> {
+        Eeprom::new(&mut self.i2c)
+    }
+}
+
+fn bcd<E>(value: Option<u8>) -> Result<u8, Error<E>> {
+    value.ok_or(Error::InvalidBcd)
+}
+
+/// Decodes ST and EXTOSC from a block read starting at RTCSEC and covering CONTROL
+fn decode_clock_config(data: &[u8]) -> ClockConfig {
+    let clock_source = if reg::<Control>(data).extosc() {
+        ClockSource::ExtClock
+    } else {
+        ClockSource::ExtCrystal
+    };
+
+    ClockConfig {
+        enabled: reg::<RtcSec>(data).st(),
+        clock_source,
+    }
+}
+
+/// Decodes RTCSEC through RTCYEAR, ignoring the weekday
+fn decode_datetime<E>(data: &[u8], century: u8) -> Result<DateTime, Error<E>> {
+    let secs = bcd(reg::<RtcSec>(data).seconds())?;
+    let min = bcd(reg::<RtcMin>(data).minutes())?;
+    let hour = bcd(reg::<RtcHour>(data).hour())?;
+    let day = bcd(reg::<RtcDate>(data).day())?;
+    let rtcmth = reg::<RtcMth>(data);
+    let month = bcd(rtcmth.month())?;
+    let year_in_century = bcd(reg::<RtcYear>(data).year())?;
+    let year = year_in_century as u16 + century as u16 * 100;
+
+    if year > 9999 {
+        return Err(Error::YearOutOfRange);
+    }
+
+    // The device sets LPYR for every year divisible by 4
+    if year_in_century.is_multiple_of(4) != rtcmth.lpyr() {
+        return Err(Error::LeapYearMismatch);
+    }
+
+    // So it also counts a February 29 in century years that aren't leap years, such as 2100
+    if is_skipped_leap_day(year, month, day) {
+        return DateTime::new(year, 3, 1, hour, min, secs).ok_or(Error::InvalidDate);
+    }
+
+    DateTime::new(year, month, day, hour, min, secs).ok_or(Error::InvalidDate)
+}
+
+/// Whether the device is on the February 29 it counts in a century year that isn't a leap year
+fn is_skipped_leap_day(year: u16, month: u8, day: u8) -> bool {
+    month == 2 && day == 29 && !datetime::is_leap_year(year)
+}
+
+/// Century tracking record: the century, the year within the century it was last seen with
+/// and the complement of the century as a check
+fn encode_century_record(century: u8, year: u8) -> [u8; CENTURY_RECORD_SIZE] {
+    [century, year, !century]
+}
+
+fn decode_century_record(record: &[u8; CENTURY_RECORD_SIZE]) -> Option<(u8, u8)> {
+    let [century, year, check] = *record;
+
+    if check != !century || year > 99 {
+        return None;
+    }
+
+    Some((century, year))
+}
+
+/// Encodes a weekday as the 1 to 7 value stored by the device
+fn encode_weekday(weekday: Weekday, first_weekday: Weekday) -> u8 {
+    weekday.days_since(first_weekday) + 1
+}
+
+fn decode_weekday<E>(value: u8, first_weekday: Weekday) -> Result<Weekday, Error<E>> {
+    if !(1..=7).contains(&value) {
+        return Err(Error::InvalidDate);
+    }
+
+    Ok(Weekday::from_days_since_monday(
+        first_weekday.days_since_monday() + value - 1,
+    ))
+}
+
+/// Decodes a PWRxxMIN through PWRxxMTH register block
+fn decode_power_fail_timestamp<E>(
+    data: &[u8],
+    first_weekday: Weekday,
+) -> Result<PowerFailTimestamp, Error<E>> {
+    let pwrmth = power_fail_reg::<PwrMth>(data);
+
+    Ok(PowerFailTimestamp {
+        minute: bcd(power_fail_reg::<PwrMin>(data).minutes())?,
+        hour: bcd(power_fail_reg::<PwrHour>(data).hour())?,
+        day: bcd(power_fail_reg::<PwrDate>(data).day())?,
+        weekday: decode_weekday(pwrmth.weekday(), first_weekday)?,
+        month: bcd(pwrmth.month())?,
+    })
 }
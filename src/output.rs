@@ -0,0 +1,45 @@
+/// Square wave clock frequency on the MFP pin (SQWFS)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SquareWaveFrequency {
+    Hz1,
+    Hz4096,
+    Hz8192,
+    Hz32768,
+}
+
+impl SquareWaveFrequency {
+    pub(crate) fn bits(self) -> u8 {
+        match self {
+            SquareWaveFrequency::Hz1 => 0b00,
+            SquareWaveFrequency::Hz4096 => 0b01,
+            SquareWaveFrequency::Hz8192 => 0b10,
+            SquareWaveFrequency::Hz32768 => 0b11,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => SquareWaveFrequency::Hz1,
+            0b01 => SquareWaveFrequency::Hz4096,
+            0b10 => SquareWaveFrequency::Hz8192,
+            _ => SquareWaveFrequency::Hz32768,
+        }
+    }
+}
+
+/// Function of the multi-function pin (MFP)
+///
+/// The device picks the MFP function with a fixed precedence: the square wave output wins over
+/// the alarm interrupt output, which in turn wins over the general purpose output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MfpMode {
+    /// Output a square wave clock. Enabled alarms still set their flags but don't drive the pin.
+    SquareWave(SquareWaveFrequency),
+    /// Drive the pin as a general purpose output, `true` being a logic high. Only available
+    /// while both alarms are disabled.
+    GeneralPurpose(bool),
+    /// Assert the pin from the alarm interrupt flags. Requires at least one enabled alarm.
+    AlarmInterrupt,
+}
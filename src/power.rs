@@ -0,0 +1,59 @@
+use crate::{DateTime, Weekday};
+
+/// Time at which main power was lost or restored, as latched by the device. The hardware does
+/// not record a year or seconds.
+///
+/// The weekday is stored using the driver's [`first_weekday`](crate::Mcp7940n::first_weekday)
+/// mapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerFailTimestamp {
+    pub minute: u8,
+    pub hour: u8,
+    pub day: u8,
+    pub weekday: Weekday,
+    pub month: u8,
+}
+
+impl PowerFailTimestamp {
+    /// Resolves the timestamp to the most recent matching date and time that isn't after
+    /// `reference`. Returns `None` if no such date exists.
+    pub fn infer_year(&self, reference: &DateTime) -> Option<DateTime> {
+        // Going back 8 years is always enough to find a Feb 29, even across a skipped century
+        // leap year
+        (reference.year().saturating_sub(8)..=reference.year())
+            .rev()
+            .filter_map(|year| DateTime::new(year, self.month, self.day, self.hour, self.minute, 0))
+            .find(|datetime| datetime <= reference)
+    }
+}
+
+/// The power-down and power-up timestamps of the last power failure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerFailTimestamps {
+    pub power_down: PowerFailTimestamp,
+    pub power_up: PowerFailTimestamp,
+}
+
+impl PowerFailTimestamps {
+    /// Resolves both timestamps against `now`, assuming power came back no later than `now` and
+    /// went away no later than it came back
+    pub fn infer_years(&self, now: &DateTime) -> Option<PowerFailWindow> {
+        let power_up = self.power_up.infer_year(now)?;
+        let power_down = self.power_down.infer_year(&power_up)?;
+
+        Some(PowerFailWindow {
+            power_down,
+            power_up,
+        })
+    }
+}
+
+/// A power failure resolved to full dates and times
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerFailWindow {
+    pub power_down: DateTime,
+    pub power_up: DateTime,
+}
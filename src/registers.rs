@@ -0,0 +1,696 @@
+//! Typed views of the RTCC registers.
+//!
+//! Each register is a thin wrapper around its raw byte with accessors for the individual fields.
+//! BCD fields decode to `None` if the register holds digits that aren't valid BCD. Setters
+//! encode the value as-is, it's up to the caller to keep values within the datasheet ranges.
+
+use core::fmt;
+
+use crate::Alarm;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A register at a fixed address
+pub trait Register: sealed::Sealed + Copy {
+    const ADDRESS: u8;
+
+    fn from_bits(bits: u8) -> Self;
+    fn bits(self) -> u8;
+}
+
+/// A register that exists once per alarm module
+pub trait AlarmRegister: sealed::Sealed + Copy {
+    /// Offset from the first register of the alarm's block
+    const OFFSET: u8;
+
+    fn from_bits(bits: u8) -> Self;
+    fn bits(self) -> u8;
+
+    fn address(alarm: Alarm) -> u8 {
+        alarm.base_register() + Self::OFFSET
+    }
+}
+
+/// A register that exists once per power-fail timestamp
+pub trait PowerFailRegister: sealed::Sealed + Copy {
+    /// Offset from the first register of the timestamp's block
+    const OFFSET: u8;
+
+    fn from_bits(bits: u8) -> Self;
+    fn bits(self) -> u8;
+
+    fn address(event: PowerFailEvent) -> u8 {
+        event.base_register() + Self::OFFSET
+    }
+}
+
+/// Selects between the power-down and power-up timestamp registers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PowerFailEvent {
+    Down,
+    Up,
+}
+
+impl PowerFailEvent {
+    pub(crate) fn base_register(self) -> u8 {
+        match self {
+            PowerFailEvent::Down => 0x18,
+            PowerFailEvent::Up => 0x1C,
+        }
+    }
+}
+
+macro_rules! register {
+    (
+        $(#[$meta:meta])* $name:ident, $trait:ident, $const:ident = $value:expr,
+        [$($field:ident),*]
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name(u8);
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field()))*
+                    .finish()
+            }
+        }
+
+        #[cfg(feature = "defmt")]
+        impl defmt::Format for $name {
+            fn format(&self, f: defmt::Formatter<'_>) {
+                defmt::write!(f, "{=str} {{", stringify!($name));
+                $(defmt::write!(f, " {=str}: {},", stringify!($field), self.$field());)*
+                defmt::write!(f, " }}");
+            }
+        }
+
+        impl sealed::Sealed for $name {}
+
+        impl $trait for $name {
+            const $const: u8 = $value;
+
+            fn from_bits(bits: u8) -> Self {
+                Self(bits)
+            }
+
+            fn bits(self) -> u8 {
+                self.0
+            }
+        }
+    };
+}
+
+macro_rules! flag {
+    ($(#[$meta:meta])* $get:ident, $bit:expr) => {
+        $(#[$meta])*
+        pub fn $get(self) -> bool {
+            self.0 & (1 << $bit) != 0
+        }
+    };
+    // Read-only to the driver, but the simulator needs to update it
+    ($(#[$meta:meta])* $get:ident, sim $set:ident, $bit:expr) => {
+        flag!($(#[$meta])* $get, $bit);
+
+        #[cfg(feature = "sim")]
+        pub(crate) fn $set(&mut self, value: bool) {
+            if value {
+                self.0 |= 1 << $bit;
+            } else {
+                self.0 &= !(1 << $bit);
+            }
+        }
+    };
+    // Set by the device, the driver only ever clears it
+    ($(#[$meta:meta])* $get:ident, crate $set:ident, $bit:expr) => {
+        flag!($(#[$meta])* $get, $bit);
+
+        pub(crate) fn $set(&mut self, value: bool) {
+            if value {
+                self.0 |= 1 << $bit;
+            } else {
+                self.0 &= !(1 << $bit);
+            }
+        }
+    };
+    ($(#[$meta:meta])* $get:ident, $set:ident, $bit:expr) => {
+        flag!($(#[$meta])* $get, $bit);
+
+        pub fn $set(&mut self, value: bool) {
+            if value {
+                self.0 |= 1 << $bit;
+            } else {
+                self.0 &= !(1 << $bit);
+            }
+        }
+    };
+}
+
+macro_rules! field {
+    ($(#[$meta:meta])* $get:ident, $set:ident, $mask:expr, $shift:expr) => {
+        $(#[$meta])*
+        pub fn $get(self) -> u8 {
+            (self.0 & $mask) >> $shift
+        }
+
+        pub fn $set(&mut self, value: u8) {
+            self.0 = (self.0 & !$mask) | ((value << $shift) & $mask);
+        }
+    };
+}
+
+macro_rules! bcd {
+    ($(#[$meta:meta])* $get:ident, $set:ident, $mask:expr) => {
+        $(#[$meta])*
+        pub fn $get(self) -> Option<u8> {
+            bcd_decode(self.0 & $mask)
+        }
+
+        pub fn $set(&mut self, value: u8) {
+            self.0 = (self.0 & !$mask) | (bcd_encode(value) & $mask);
+        }
+    };
+}
+
+macro_rules! hour {
+    () => {
+        flag!(
+            /// Whether the hour is stored in 12-hour format
+            twelve_hour,
+            6
+        );
+
+        /// The hour in 24-hour format, whichever format it is stored in
+        pub fn hour(self) -> Option<u8> {
+            decode_hour(self.0)
+        }
+    };
+}
+
+register!(
+    /// RTCSEC
+    RtcSec, Register, ADDRESS = 0x00,
+    [st, seconds]
+);
+
+impl RtcSec {
+    flag!(
+        /// Start oscillator bit
+        st,
+        set_st,
+        7
+    );
+    bcd!(seconds, set_seconds, 0b0111_1111);
+}
+
+register!(
+    /// RTCMIN
+    RtcMin, Register, ADDRESS = 0x01,
+    [minutes]
+);
+
+impl RtcMin {
+    bcd!(minutes, set_minutes, 0b0111_1111);
+}
+
+register!(
+    /// RTCHOUR
+    RtcHour, Register, ADDRESS = 0x02,
+    [twelve_hour, hour]
+);
+
+impl RtcHour {
+    hour!();
+
+    /// Stores a 24-hour format `hour` in the requested format
+    pub fn set_hour(&mut self, hour: u8, twelve_hour: bool) {
+        self.0 = encode_hour(hour, twelve_hour);
+    }
+}
+
+register!(
+    /// RTCWKDAY
+    RtcWkday, Register, ADDRESS = 0x03,
+    [oscrun, pwrfail, vbaten, weekday]
+);
+
+impl RtcWkday {
+    flag!(
+        /// Oscillator running status, read-only
+        oscrun,
+        sim set_oscrun,
+        5
+    );
+    flag!(
+        /// Power failure status. Can't be written high, any write to RTCWKDAY clears it.
+        pwrfail,
+        crate set_pwrfail,
+        4
+    );
+    flag!(
+        /// Battery backup supply enable
+        vbaten,
+        set_vbaten,
+        3
+    );
+    field!(
+        /// Day of week from 1 to 7, the meaning of each value is user-defined
+        weekday,
+        set_weekday,
+        0b0000_0111,
+        0
+    );
+}
+
+register!(
+    /// RTCDATE
+    RtcDate, Register, ADDRESS = 0x04,
+    [day]
+);
+
+impl RtcDate {
+    bcd!(day, set_day, 0b0011_1111);
+}
+
+register!(
+    /// RTCMTH
+    RtcMth, Register, ADDRESS = 0x05,
+    [lpyr, month]
+);
+
+impl RtcMth {
+    flag!(
+        /// Leap year, read-only
+        lpyr,
+        sim set_lpyr,
+        5
+    );
+    bcd!(month, set_month, 0b0001_1111);
+}
+
+register!(
+    /// RTCYEAR
+    RtcYear, Register, ADDRESS = 0x06,
+    [year]
+);
+
+impl RtcYear {
+    bcd!(
+        /// Year within the century, 0 to 99
+        year,
+        set_year,
+        0b1111_1111
+    );
+}
+
+register!(
+    /// CONTROL
+    Control, Register, ADDRESS = 0x07,
+    [out, sqwen, alm1en, alm0en, extosc, crstrim, sqwfs]
+);
+
+impl Control {
+    flag!(
+        /// General purpose output level
+        out,
+        set_out,
+        7
+    );
+    flag!(
+        /// Square wave output enable
+        sqwen,
+        set_sqwen,
+        6
+    );
+    flag!(
+        /// Alarm 1 enable
+        alm1en,
+        set_alm1en,
+        5
+    );
+    flag!(
+        /// Alarm 0 enable
+        alm0en,
+        set_alm0en,
+        4
+    );
+    flag!(
+        /// External 32.768 kHz clock input enable
+        extosc,
+        set_extosc,
+        3
+    );
+    flag!(
+        /// Coarse trim mode enable
+        crstrim,
+        set_crstrim,
+        2
+    );
+    field!(
+        /// Square wave frequency select
+        sqwfs,
+        set_sqwfs,
+        0b0000_0011,
+        0
+    );
+
+    pub fn alarm_enabled(self, alarm: Alarm) -> bool {
+        match alarm {
+            Alarm::Zero => self.alm0en(),
+            Alarm::One => self.alm1en(),
+        }
+    }
+
+    pub fn set_alarm_enabled(&mut self, alarm: Alarm, enabled: bool) {
+        match alarm {
+            Alarm::Zero => self.set_alm0en(enabled),
+            Alarm::One => self.set_alm1en(enabled),
+        }
+    }
+}
+
+register!(
+    /// OSCTRIM
+    OscTrim, Register, ADDRESS = 0x08,
+    [sign, trimval]
+);
+
+impl OscTrim {
+    flag!(
+        /// Set to add clocks, clear to subtract them
+        sign,
+        set_sign,
+        7
+    );
+    field!(
+        /// Number of trim steps, 0 disables trimming
+        trimval,
+        set_trimval,
+        0b0111_1111,
+        0
+    );
+}
+
+register!(
+    /// ALMxSEC
+    AlmSec, AlarmRegister, OFFSET = 0,
+    [seconds]
+);
+
+impl AlmSec {
+    bcd!(seconds, set_seconds, 0b0111_1111);
+}
+
+register!(
+    /// ALMxMIN
+    AlmMin, AlarmRegister, OFFSET = 1,
+    [minutes]
+);
+
+impl AlmMin {
+    bcd!(minutes, set_minutes, 0b0111_1111);
+}
+
+register!(
+    /// ALMxHOUR. The 12/24 bit is read-only and mirrors RTCHOUR.
+    AlmHour, AlarmRegister, OFFSET = 2,
+    [twelve_hour, hour]
+);
+
+impl AlmHour {
+    hour!();
+
+    /// Stores a 24-hour format `hour` in the requested format, which must match RTCHOUR
+    pub fn set_hour(&mut self, hour: u8, twelve_hour: bool) {
+        self.0 = encode_hour(hour, twelve_hour);
+    }
+}
+
+register!(
+    /// ALMxWKDAY
+    AlmWkday, AlarmRegister, OFFSET = 3,
+    [almpol, almmsk, almif, weekday]
+);
+
+impl AlmWkday {
+    flag!(
+        /// Alarm output polarity, shared by both alarms and read-only in ALM1WKDAY
+        almpol,
+        set_almpol,
+        7
+    );
+    field!(
+        /// Alarm mask
+        almmsk,
+        set_almmsk,
+        0b0111_0000,
+        4
+    );
+    flag!(
+        /// Alarm interrupt flag. Can't be written high, any write to ALMxWKDAY clears it.
+        almif,
+        crate set_almif,
+        3
+    );
+    field!(
+        /// Day of week from 1 to 7
+        weekday,
+        set_weekday,
+        0b0000_0111,
+        0
+    );
+}
+
+register!(
+    /// ALMxDATE
+    AlmDate, AlarmRegister, OFFSET = 4,
+    [day]
+);
+
+impl AlmDate {
+    bcd!(day, set_day, 0b0011_1111);
+}
+
+register!(
+    /// ALMxMTH
+    AlmMth, AlarmRegister, OFFSET = 5,
+    [month]
+);
+
+impl AlmMth {
+    bcd!(month, set_month, 0b0001_1111);
+}
+
+register!(
+    /// PWRxxMIN
+    PwrMin, PowerFailRegister, OFFSET = 0,
+    [minutes]
+);
+
+impl PwrMin {
+    bcd!(minutes, set_minutes, 0b0111_1111);
+}
+
+register!(
+    /// PWRxxHOUR
+    PwrHour, PowerFailRegister, OFFSET = 1,
+    [twelve_hour, hour]
+);
+
+impl PwrHour {
+    hour!();
+}
+
+register!(
+    /// PWRxxDATE
+    PwrDate, PowerFailRegister, OFFSET = 2,
+    [day]
+);
+
+impl PwrDate {
+    bcd!(day, set_day, 0b0011_1111);
+}
+
+register!(
+    /// PWRxxMTH
+    PwrMth, PowerFailRegister, OFFSET = 3,
+    [weekday, month]
+);
+
+impl PwrMth {
+    field!(
+        /// Day of week from 1 to 7
+        weekday,
+        set_weekday,
+        0b1110_0000,
+        5
+    );
+    bcd!(month, set_month, 0b0001_1111);
+}
+
+/// Every register from RTCSEC through PWRUPMTH, decoded. The `Debug` output lists each field,
+/// so `{:#?}` gives a readable dump of the whole device state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterDump {
+    pub rtcsec: RtcSec,
+    pub rtcmin: RtcMin,
+    pub rtchour: RtcHour,
+    pub rtcwkday: RtcWkday,
+    pub rtcdate: RtcDate,
+    pub rtcmth: RtcMth,
+    pub rtcyear: RtcYear,
+    pub control: Control,
+    pub osctrim: OscTrim,
+    pub alarm0: AlarmRegisters,
+    pub alarm1: AlarmRegisters,
+    pub power_down: PowerFailRegisters,
+    pub power_up: PowerFailRegisters,
+}
+
+/// Number of bytes covered by a [`RegisterDump`], starting at RTCSEC
+pub(crate) const REGISTER_DUMP_SIZE: usize = 0x20;
+
+impl RegisterDump {
+    pub(crate) fn from_bytes(data: &[u8; REGISTER_DUMP_SIZE]) -> Self {
+        Self {
+            rtcsec: reg(data),
+            rtcmin: reg(data),
+            rtchour: reg(data),
+            rtcwkday: reg(data),
+            rtcdate: reg(data),
+            rtcmth: reg(data),
+            rtcyear: reg(data),
+            control: reg(data),
+            osctrim: reg(data),
+            alarm0: AlarmRegisters::from_bytes(&data[Alarm::Zero.base_register() as usize..]),
+            alarm1: AlarmRegisters::from_bytes(&data[Alarm::One.base_register() as usize..]),
+            power_down: PowerFailRegisters::from_bytes(
+                &data[PowerFailEvent::Down.base_register() as usize..],
+            ),
+            power_up: PowerFailRegisters::from_bytes(
+                &data[PowerFailEvent::Up.base_register() as usize..],
+            ),
+        }
+    }
+}
+
+/// The registers of one alarm module
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AlarmRegisters {
+    pub almsec: AlmSec,
+    pub almmin: AlmMin,
+    pub almhour: AlmHour,
+    pub almwkday: AlmWkday,
+    pub almdate: AlmDate,
+    pub almmth: AlmMth,
+}
+
+impl AlarmRegisters {
+    fn from_bytes(data: &[u8]) -> Self {
+        Self {
+            almsec: alarm_reg(data),
+            almmin: alarm_reg(data),
+            almhour: alarm_reg(data),
+            almwkday: alarm_reg(data),
+            almdate: alarm_reg(data),
+            almmth: alarm_reg(data),
+        }
+    }
+}
+
+/// The registers of one power-fail timestamp
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerFailRegisters {
+    pub pwrmin: PwrMin,
+    pub pwrhour: PwrHour,
+    pub pwrdate: PwrDate,
+    pub pwrmth: PwrMth,
+}
+
+impl PowerFailRegisters {
+    fn from_bytes(data: &[u8]) -> Self {
+        Self {
+            pwrmin: power_fail_reg(data),
+            pwrhour: power_fail_reg(data),
+            pwrdate: power_fail_reg(data),
+            pwrmth: power_fail_reg(data),
+        }
+    }
+}
+
+/// Picks a register out of a block read starting at RTCSEC
+pub(crate) fn reg<R: Register>(data: &[u8]) -> R {
+    R::from_bits(data[R::ADDRESS as usize])
+}
+
+/// Picks a register out of a block read starting at ALMxSEC
+pub(crate) fn alarm_reg<R: AlarmRegister>(data: &[u8]) -> R {
+    R::from_bits(data[R::OFFSET as usize])
+}
+
+/// Picks a register out of a block read starting at PWRxxMIN
+pub(crate) fn power_fail_reg<R: PowerFailRegister>(data: &[u8]) -> R {
+    R::from_bits(data[R::OFFSET as usize])
+}
+
+fn bcd_encode(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+fn bcd_decode(value: u8) -> Option<u8> {
+    let tens = value >> 4;
+    let ones = value & 0b0000_1111;
+
+    if tens > 9 || ones > 9 {
+        return None;
+    }
+
+    Some((tens * 10) + ones)
+}
+
+/// Decodes an RTCHOUR style register into a 24hr hour, regardless of the 12/24 bit
+fn decode_hour(reg: u8) -> Option<u8> {
+    let hr_12 = (reg & 0b0100_0000) != 0;
+
+    // We want to always convert to 24hr time
+    if hr_12 {
+        let pm = (reg & 0b0010_0000) != 0;
+        let hr = bcd_decode(reg & 0b0001_1111)?;
+
+        match (hr, pm) {
+            (1..=11, false) => Some(hr),
+            (12, false) => Some(0),
+            (1..=11, true) => Some(hr + 12),
+            (12, true) => Some(12),
+            _ => None,
+        }
+    } else {
+        bcd_decode(reg & 0b0011_1111)
+    }
+}
+
+/// Encodes a 24hr hour into an RTCHOUR style register in the requested format
+fn encode_hour(hour: u8, twelve_hour: bool) -> u8 {
+    if twelve_hour {
+        let pm = hour >= 12;
+        let hr = match hour % 12 {
+            0 => 12,
+            hr => hr,
+        };
+
+        let mut reg = 0b0100_0000 | bcd_encode(hr);
+        if pm {
+            reg |= 0b0010_0000;
+        }
+        reg
+    } else {
+        bcd_encode(hour)
+    }
+}
@@ -0,0 +1,240 @@
+//! Implementations of the [`rtcc`](::rtcc) traits.
+//!
+//! The traits number the weekday from Sunday as 1, so these always store it that way regardless
+//! of [`first_weekday`](Mcp7940n::first_weekday). Set that to Sunday as well when mixing these
+//! with the driver's own weekday methods.
+
+use ::rtcc::{DateTimeAccess, Hours, Rtcc};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use embedded_hal::i2c::I2c;
+
+use crate::registers::{Register, RtcDate, RtcHour, RtcMin, RtcMth, RtcSec, RtcWkday, RtcYear};
+use crate::variant::Variant;
+use crate::{bcd, encode_weekday, DateTime, Error, HourMode, Mcp7940n, Weekday};
+
+impl<I: I2c, V: Variant> DateTimeAccess for Mcp7940n<I, V> {
+    type Error = Error<I::Error>;
+
+    fn datetime(&mut self) -> Result<NaiveDateTime, Self::Error> {
+        self.now().map(NaiveDateTime::from)
+    }
+
+    /// Sets the date and time, switching the device over to 24-hour mode as the trait expects
+    fn set_datetime(&mut self, datetime: &NaiveDateTime) -> Result<(), Self::Error> {
+        self.set_hour_mode(HourMode::TwentyFourHour)?;
+
+        let datetime = DateTime::try_from(*datetime).map_err(|_| Error::YearOutOfRange)?;
+
+        self.write_datetime(datetime, Weekday::Sunday)
+    }
+}
+
+impl<I: I2c, V: Variant> Rtcc for Mcp7940n<I, V> {
+    fn seconds(&mut self) -> Result<u8, Self::Error> {
+        let rtcsec: RtcSec = self.read_register()?;
+
+        bcd(rtcsec.seconds())
+    }
+
+    fn minutes(&mut self) -> Result<u8, Self::Error> {
+        let rtcmin: RtcMin = self.read_register()?;
+
+        bcd(rtcmin.minutes())
+    }
+
+    /// Reads the hours in whichever format the device is configured for
+    fn hours(&mut self) -> Result<Hours, Self::Error> {
+        let rtchour: RtcHour = self.read_register()?;
+        let hour = bcd(rtchour.hour())?;
+
+        if !rtchour.twelve_hour() {
+            return Ok(Hours::H24(hour));
+        }
+
+        let twelve_hour = match hour % 12 {
+            0 => 12,
+            hour => hour,
+        };
+
+        if hour < 12 {
+            Ok(Hours::AM(twelve_hour))
+        } else {
+            Ok(Hours::PM(twelve_hour))
+        }
+    }
+
+    fn time(&mut self) -> Result<NaiveTime, Self::Error> {
+        Ok(NaiveDateTime::from(self.now()?).time())
+    }
+
+    /// Reads the 1 to 7 weekday value, Sunday being 1
+    fn weekday(&mut self) -> Result<u8, Self::Error> {
+        let rtcwkday: RtcWkday = self.read_register()?;
+
+        Ok(rtcwkday.weekday())
+    }
+
+    fn day(&mut self) -> Result<u8, Self::Error> {
+        let rtcdate: RtcDate = self.read_register()?;
+
+        bcd(rtcdate.day())
+    }
+
+    fn month(&mut self) -> Result<u8, Self::Error> {
+        let rtcmth: RtcMth = self.read_register()?;
+
+        bcd(rtcmth.month())
+    }
+
+    fn year(&mut self) -> Result<u16, Self::Error> {
+        let rtcyear: RtcYear = self.read_register()?;
+        let year = bcd(rtcyear.year())?;
+
+        self.update_century(year)?;
+
+        Ok(self.century as u16 * 100 + year as u16)
+    }
+
+    fn date(&mut self) -> Result<NaiveDate, Self::Error> {
+        Ok(NaiveDateTime::from(self.now()?).date())
+    }
+
+    fn set_seconds(&mut self, seconds: u8) -> Result<(), Self::Error> {
+        if seconds > 59 {
+            return Err(Error::InvalidDate);
+        }
+
+        let mut rtcsec = RtcSec::default();
+        rtcsec.set_seconds(seconds);
+
+        self.write_time_registers(&[RtcSec::ADDRESS, rtcsec.bits()])
+    }
+
+    fn set_minutes(&mut self, minutes: u8) -> Result<(), Self::Error> {
+        if minutes > 59 {
+            return Err(Error::InvalidDate);
+        }
+
+        let mut rtcmin = RtcMin::default();
+        rtcmin.set_minutes(minutes);
+
+        self.write_time_registers(&[RtcMin::ADDRESS, rtcmin.bits()])
+    }
+
+    /// Sets the hours, switching the device over to the format of `hours`. The alarm hours are
+    /// converted along with it.
+    fn set_hours(&mut self, hours: Hours) -> Result<(), Self::Error> {
+        let (hour, mode) = match hours {
+            Hours::AM(hour @ 1..=12) => (hour % 12, HourMode::TwelveHour),
+            Hours::PM(hour @ 1..=12) => (hour % 12 + 12, HourMode::TwelveHour),
+            Hours::H24(hour @ 0..=23) => (hour, HourMode::TwentyFourHour),
+            _ => return Err(Error::InvalidDate),
+        };
+
+        self.set_hour_mode(mode)?;
+
+        let mut rtchour = RtcHour::default();
+        rtchour.set_hour(hour, mode == HourMode::TwelveHour);
+
+        self.write_time_registers(&[RtcHour::ADDRESS, rtchour.bits()])
+    }
+
+    /// Sets the time, keeping the current hour format
+    fn set_time(&mut self, time: &NaiveTime) -> Result<(), Self::Error> {
+        let current: RtcHour = self.read_register()?;
+
+        let mut rtcsec = RtcSec::default();
+        rtcsec.set_seconds(time.second() as u8);
+
+        let mut rtcmin = RtcMin::default();
+        rtcmin.set_minutes(time.minute() as u8);
+
+        let mut rtchour = RtcHour::default();
+        rtchour.set_hour(time.hour() as u8, current.twelve_hour());
+
+        self.write_time_registers(&[
+            RtcSec::ADDRESS,
+            rtcsec.bits(),
+            rtcmin.bits(),
+            rtchour.bits(),
+        ])
+    }
+
+    /// Sets the 1 to 7 weekday value, Sunday being 1. This also clears PWRFAIL, as the device clears it on
+    /// any write to RTCWKDAY.
+    fn set_weekday(&mut self, weekday: u8) -> Result<(), Self::Error> {
+        if !(1..=7).contains(&weekday) {
+            return Err(Error::InvalidDate);
+        }
+
+        let mut rtcwkday: RtcWkday = self.read_register()?;
+        rtcwkday.set_weekday(weekday);
+
+        self.write_time_registers(&[RtcWkday::ADDRESS, rtcwkday.bits()])
+    }
+
+    fn set_day(&mut self, day: u8) -> Result<(), Self::Error> {
+        if !(1..=31).contains(&day) {
+            return Err(Error::InvalidDate);
+        }
+
+        let mut rtcdate = RtcDate::default();
+        rtcdate.set_day(day);
+
+        self.write_time_registers(&[RtcDate::ADDRESS, rtcdate.bits()])
+    }
+
+    fn set_month(&mut self, month: u8) -> Result<(), Self::Error> {
+        if !(1..=12).contains(&month) {
+            return Err(Error::InvalidDate);
+        }
+
+        let mut rtcmth = RtcMth::default();
+        rtcmth.set_month(month);
+
+        self.write_time_registers(&[RtcMth::ADDRESS, rtcmth.bits()])
+    }
+
+    fn set_year(&mut self, year: u16) -> Result<(), Self::Error> {
+        let (century, year) = self.split_year(year)?;
+
+        let mut rtcyear = RtcYear::default();
+        rtcyear.set_year(year);
+
+        self.write_time_registers(&[RtcYear::ADDRESS, rtcyear.bits()])?;
+
+        self.store_century(century, year)
+    }
+
+    /// Sets the date along with the matching weekday. This also clears PWRFAIL, as the device
+    /// clears it on any write to RTCWKDAY.
+    fn set_date(&mut self, date: &NaiveDate) -> Result<(), Self::Error> {
+        let year = u16::try_from(date.year()).map_err(|_| Error::YearOutOfRange)?;
+        let (century, year) = self.split_year(year)?;
+
+        let mut rtcwkday: RtcWkday = self.read_register()?;
+        rtcwkday.set_weekday(encode_weekday(
+            Weekday::from(date.weekday()),
+            Weekday::Sunday,
+        ));
+
+        let mut rtcdate = RtcDate::default();
+        rtcdate.set_day(date.day() as u8);
+
+        let mut rtcmth = RtcMth::default();
+        rtcmth.set_month(date.month() as u8);
+
+        let mut rtcyear = RtcYear::default();
+        rtcyear.set_year(year);
+
+        self.write_time_registers(&[
+            RtcWkday::ADDRESS,
+            rtcwkday.bits(),
+            rtcdate.bits(),
+            rtcmth.bits(),
+            rtcyear.bits(),
+        ])?;
+
+        self.store_century(century, year)
+    }
+}
@@ -0,0 +1,632 @@
+//! In-memory model of an MCP7940N for exercising the driver on the host.
+//!
+//! [`Simulator`] implements the I2C traits on a shared reference, so the driver can own one end
+//! while the test keeps the other to advance virtual time, cut power or inspect registers.
+//! Nothing happens in real time, the clock only moves when [`Simulator::advance`] is called or
+//! when the driver waits on the [`Delay`] returned by [`Simulator::delay`].
+//!
+//! ```
+//! use core::time::Duration;
+//! use mcp7940n::sim::Simulator;
+//! use mcp7940n::{ClockConfig, ClockSource, DateTime, Mcp7940n};
+//!
+//! let sim = Simulator::new();
+//! let mut rtc = Mcp7940n::new(&sim);
+//!
+//! rtc.set_datetime(DateTime::new(2024, 2, 28, 23, 59, 58).unwrap())
+//!     .unwrap();
+//! rtc.configure_clock(&ClockConfig {
+//!     enabled: true,
+//!     clock_source: ClockSource::ExtCrystal,
+//! })
+//! .unwrap();
+//!
+//! // The oscillator takes a millisecond to start before the seconds start counting
+//! sim.advance(Duration::from_millis(3001));
+//!
+//! assert_eq!(rtc.now().unwrap(), DateTime::new(2024, 2, 29, 0, 0, 1).unwrap());
+//! ```
+//!
+//! The model covers timekeeping in 12 and 24-hour mode, the ST/EXTOSC to OSCRUN start-up delay,
+//! both alarms with their interrupt flags and the MFP output, battery backup with power-fail
+//! timestamps, and SRAM. Digital trimming and the square wave output are not modeled. OSCRUN
+//! clears as soon as the oscillator is disabled rather than after TOSF.
+
+use core::cell::RefCell;
+use core::time::Duration;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+use crate::datetime::days_in_month;
+use crate::registers::{
+    AlarmRegister, AlmDate, AlmHour, AlmMin, AlmMth, AlmSec, AlmWkday, Control, PowerFailEvent,
+    PowerFailRegister, PwrDate, PwrHour, PwrMin, PwrMth, Register, RtcDate, RtcHour, RtcMin,
+    RtcMth, RtcSec, RtcWkday, RtcYear,
+};
+use crate::Alarm;
+
+const ADDRESS: u8 = 0b110_1111;
+
+const RTCC_END: u8 = 0x1F;
+const SRAM_START: u8 = 0x20;
+const SRAM_END: u8 = 0x5F;
+
+/// Bits that can be written over I2C, for each address from RTCSEC through PWRUPMTH. Bits that
+/// are read-only or unimplemented are left alone by writes.
+const WRITABLE: [u8; 0x20] = [
+    0xFF, 0x7F, 0x7F, 0x0F, 0x3F, 0x1F, 0xFF, 0xFF, 0xFF, // RTCSEC - OSCTRIM
+    0x00, // Reserved
+    0x7F, 0x7F, 0x3F, 0xF7, 0x3F, 0x1F, // ALM0
+    0x00, // Reserved
+    0x7F, 0x7F, 0x3F, 0x77, 0x3F, 0x1F, // ALM1
+    0x00, // Reserved
+    0x7F, 0x7F, 0x3F, 0xFF, // PWRDN
+    0x7F, 0x7F, 0x3F, 0xFF, // PWRUP
+];
+
+/// Time the oscillator takes from being enabled to OSCRUN setting, 32 cycles at 32.768 kHz
+const DEFAULT_STARTUP_DELAY: Duration = Duration::from_millis(1);
+
+/// The 12/24 bit of the hour registers
+const TWELVE_HOUR: u8 = 0b0100_0000;
+
+/// A simulated MCP7940N, see the [module documentation](self)
+#[derive(Debug, Default)]
+pub struct Simulator {
+    state: RefCell<State>,
+}
+
+impl Simulator {
+    /// A device in its power-on reset state, with a crystal fitted and no battery
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances virtual time, ticking the clock if the oscillator is running
+    pub fn advance(&self, duration: Duration) {
+        self.state.borrow_mut().advance(duration);
+    }
+
+    /// A delay that advances virtual time rather than waiting
+    pub fn delay(&self) -> Delay<'_> {
+        Delay { simulator: self }
+    }
+
+    /// Removes main power. With VBATEN set the device keeps time on the backup supply and logs
+    /// the power-down timestamp, otherwise it loses all state. The device doesn't respond on
+    /// the bus until power is restored.
+    pub fn power_down(&self) {
+        self.state.borrow_mut().power_down();
+    }
+
+    /// Restores main power, logging the power-up timestamp if the device ran from the backup
+    /// supply, or going through a power-on reset if it didn't
+    pub fn power_up(&self) {
+        self.state.borrow_mut().power_up();
+    }
+
+    /// Whether a working crystal is fitted between X1 and X2, defaults to `true`. Removing it
+    /// while the oscillator runs from the crystal stops the clock, as a failing crystal would.
+    pub fn set_crystal(&self, fitted: bool) {
+        let mut state = self.state.borrow_mut();
+        state.crystal = fitted;
+        state.update_oscillator();
+    }
+
+    /// Whether a 32.768 kHz clock is driving X1, defaults to `false`
+    pub fn set_external_clock(&self, present: bool) {
+        let mut state = self.state.borrow_mut();
+        state.external_clock = present;
+        state.update_oscillator();
+    }
+
+    /// Sets the time from the oscillator being enabled to OSCRUN setting, defaults to 1 ms.
+    /// Real crystals can take hundreds of milliseconds.
+    pub fn set_startup_delay(&self, delay: Duration) {
+        self.state.borrow_mut().startup_delay = delay;
+    }
+
+    /// Reads a register or SRAM byte directly, without going through the bus. Panics if
+    /// `address` is past the end of SRAM.
+    pub fn peek(&self, address: u8) -> u8 {
+        self.state.borrow().registers[address as usize]
+    }
+
+    /// Writes a register or SRAM byte directly, bypassing read-only bits and write side effects.
+    /// Panics if `address` is past the end of SRAM.
+    pub fn poke(&self, address: u8, value: u8) {
+        self.state.borrow_mut().registers[address as usize] = value;
+    }
+
+    /// Level of the MFP pin, or `None` while it outputs a square wave
+    pub fn mfp(&self) -> Option<bool> {
+        self.state.borrow().mfp()
+    }
+}
+
+impl ErrorType for &Simulator {
+    type Error = ErrorKind;
+}
+
+impl I2c for &Simulator {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.state.borrow_mut().transaction(address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::i2c::I2c for &Simulator {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.state.borrow_mut().transaction(address, operations)
+    }
+}
+
+/// Delay that advances the virtual time of a [`Simulator`]
+#[derive(Debug)]
+pub struct Delay<'a> {
+    simulator: &'a Simulator,
+}
+
+impl DelayNs for Delay<'_> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.simulator.advance(Duration::from_nanos(ns as u64));
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for Delay<'_> {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.simulator.advance(Duration::from_nanos(ns as u64));
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    registers: [u8; SRAM_END as usize + 1],
+    pointer: u8,
+    powered: bool,
+    crystal: bool,
+    external_clock: bool,
+    startup_delay: Duration,
+    /// Time the oscillator has been enabled without OSCRUN set
+    starting_for: Duration,
+    /// Time since the last second tick
+    subsecond: Duration,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let mut state = Self {
+            registers: [0; SRAM_END as usize + 1],
+            pointer: 0,
+            powered: true,
+            crystal: true,
+            external_clock: false,
+            startup_delay: DEFAULT_STARTUP_DELAY,
+            starting_for: Duration::ZERO,
+            subsecond: Duration::ZERO,
+        };
+        state.reset();
+        state
+    }
+}
+
+impl State {
+    /// Puts the RTCC registers in their power-on reset state. SRAM isn't initialized by a reset
+    /// so is left as it is.
+    fn reset(&mut self) {
+        self.registers[..=RTCC_END as usize].fill(0);
+        self.registers[RtcWkday::ADDRESS as usize] = 0x01;
+        self.registers[RtcDate::ADDRESS as usize] = 0x01;
+        self.registers[RtcMth::ADDRESS as usize] = 0x01;
+        self.registers[RtcYear::ADDRESS as usize] = 0x01;
+        self.registers[Control::ADDRESS as usize] = 0x80;
+
+        for alarm in [Alarm::Zero, Alarm::One] {
+            self.registers[AlmWkday::address(alarm) as usize] = 0x01;
+            self.registers[AlmDate::address(alarm) as usize] = 0x01;
+            self.registers[AlmMth::address(alarm) as usize] = 0x01;
+        }
+
+        self.pointer = 0;
+        self.starting_for = Duration::ZERO;
+        self.subsecond = Duration::ZERO;
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), ErrorKind> {
+        if address != ADDRESS || !self.powered {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+
+        // Adjacent writes are one continuous write, a write after a read starts a new one
+        let mut new_write = true;
+        for operation in operations {
+            match operation {
+                Operation::Write(data) => {
+                    for &byte in data.iter() {
+                        if new_write {
+                            self.pointer = byte;
+                            new_write = false;
+                        } else {
+                            self.write(byte);
+                        }
+                    }
+                }
+                Operation::Read(data) => {
+                    for byte in data.iter_mut() {
+                        *byte = self.read();
+                    }
+                    new_write = true;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read(&mut self) -> u8 {
+        let value = self
+            .registers
+            .get(self.pointer as usize)
+            .copied()
+            .unwrap_or(0);
+        self.pointer = next_address(self.pointer);
+
+        value
+    }
+
+    fn write(&mut self, value: u8) {
+        let address = self.pointer;
+        self.pointer = next_address(address);
+
+        match address {
+            0..=RTCC_END => {}
+            SRAM_START..=SRAM_END => {
+                self.registers[address as usize] = value;
+                return;
+            }
+            _ => return,
+        }
+
+        let writable = WRITABLE[address as usize];
+        let register = &mut self.registers[address as usize];
+        *register = (*register & !writable) | (value & writable);
+
+        if address == RtcWkday::ADDRESS {
+            // Any write to RTCWKDAY clears PWRFAIL, which also clears the timestamps
+            let mut rtcwkday: RtcWkday = self.register();
+            if rtcwkday.pwrfail() {
+                rtcwkday.set_pwrfail(false);
+                self.set_register(rtcwkday);
+                self.registers[PwrMin::address(PowerFailEvent::Down) as usize..=RTCC_END as usize]
+                    .fill(0);
+            }
+        }
+
+        for alarm in [Alarm::Zero, Alarm::One] {
+            // Any write to ALMxWKDAY clears ALMxIF
+            if address == AlmWkday::address(alarm) {
+                let mut almwkday: AlmWkday = self.alarm_register(alarm);
+                almwkday.set_almif(false);
+                self.set_alarm_register(alarm, almwkday);
+            }
+        }
+
+        if address == RtcYear::ADDRESS {
+            self.update_leap_year();
+        }
+
+        self.update_mirrors();
+        self.update_oscillator();
+        self.check_alarms();
+    }
+
+    fn register<R: Register>(&self) -> R {
+        R::from_bits(self.registers[R::ADDRESS as usize])
+    }
+
+    fn set_register<R: Register>(&mut self, register: R) {
+        self.registers[R::ADDRESS as usize] = register.bits();
+    }
+
+    fn alarm_register<R: AlarmRegister>(&self, alarm: Alarm) -> R {
+        R::from_bits(self.registers[R::address(alarm) as usize])
+    }
+
+    fn set_alarm_register<R: AlarmRegister>(&mut self, alarm: Alarm, register: R) {
+        self.registers[R::address(alarm) as usize] = register.bits();
+    }
+
+    fn set_power_fail_register<R: PowerFailRegister>(
+        &mut self,
+        event: PowerFailEvent,
+        register: R,
+    ) {
+        self.registers[R::address(event) as usize] = register.bits();
+    }
+
+    /// Copies the read-only mirrors of the hour format and alarm polarity
+    fn update_mirrors(&mut self) {
+        let twelve_hour = self.register::<RtcHour>().twelve_hour();
+        let almpol = self.alarm_register::<AlmWkday>(Alarm::Zero).almpol();
+
+        // ALMxHOUR's 12/24 bit is read-only, so it's the one bit set_hour can't be used for
+        for alarm in [Alarm::Zero, Alarm::One] {
+            let address = AlmHour::address(alarm) as usize;
+            self.registers[address] &= !TWELVE_HOUR;
+            if twelve_hour {
+                self.registers[address] |= TWELVE_HOUR;
+            }
+        }
+
+        let mut alm1wkday: AlmWkday = self.alarm_register(Alarm::One);
+        alm1wkday.set_almpol(almpol);
+        self.set_alarm_register(Alarm::One, alm1wkday);
+    }
+
+    fn update_leap_year(&mut self) {
+        let year = self.register::<RtcYear>().year().unwrap_or(0);
+
+        let mut rtcmth: RtcMth = self.register();
+        rtcmth.set_lpyr(year.is_multiple_of(4));
+        self.set_register(rtcmth);
+    }
+
+    /// Whether the selected clock source is enabled and present
+    fn oscillator_enabled(&self) -> bool {
+        let control: Control = self.register();
+
+        if control.extosc() {
+            self.external_clock
+        } else {
+            self.register::<RtcSec>().st() && self.crystal
+        }
+    }
+
+    fn oscillator_running(&self) -> bool {
+        self.register::<RtcWkday>().oscrun()
+    }
+
+    fn set_oscillator_running(&mut self, running: bool) {
+        let mut rtcwkday: RtcWkday = self.register();
+        rtcwkday.set_oscrun(running);
+        self.set_register(rtcwkday);
+    }
+
+    fn update_oscillator(&mut self) {
+        if !self.oscillator_enabled() {
+            self.set_oscillator_running(false);
+            self.starting_for = Duration::ZERO;
+            self.subsecond = Duration::ZERO;
+        }
+    }
+
+    fn advance(&mut self, mut duration: Duration) {
+        if !self.oscillator_enabled() {
+            return;
+        }
+
+        if !self.oscillator_running() {
+            let remaining = self.startup_delay.saturating_sub(self.starting_for);
+            if duration < remaining {
+                self.starting_for += duration;
+                return;
+            }
+
+            duration -= remaining;
+            self.set_oscillator_running(true);
+        }
+
+        let elapsed = self.subsecond + duration;
+        let seconds = elapsed.as_secs();
+        self.subsecond = elapsed - Duration::from_secs(seconds);
+
+        for _ in 0..seconds {
+            self.tick();
+        }
+    }
+
+    /// Advances the time registers by one second, carrying through to the year
+    fn tick(&mut self) {
+        let mut rtcsec: RtcSec = self.register();
+        let seconds = rtcsec.seconds().unwrap_or(0) + 1;
+        rtcsec.set_seconds(seconds % 60);
+        self.set_register(rtcsec);
+
+        if seconds >= 60 {
+            self.tick_minute();
+        }
+
+        self.check_alarms();
+    }
+
+    fn tick_minute(&mut self) {
+        let mut rtcmin: RtcMin = self.register();
+        let minutes = rtcmin.minutes().unwrap_or(0) + 1;
+        rtcmin.set_minutes(minutes % 60);
+        self.set_register(rtcmin);
+
+        if minutes < 60 {
+            return;
+        }
+
+        let mut rtchour: RtcHour = self.register();
+        let hour = rtchour.hour().unwrap_or(0) + 1;
+        rtchour.set_hour(hour % 24, rtchour.twelve_hour());
+        self.set_register(rtchour);
+
+        if hour >= 24 {
+            self.tick_day();
+        }
+    }
+
+    fn tick_day(&mut self) {
+        let mut rtcwkday: RtcWkday = self.register();
+        rtcwkday.set_weekday(rtcwkday.weekday() % 7 + 1);
+        self.set_register(rtcwkday);
+
+        let year = self.register::<RtcYear>().year().unwrap_or(0);
+        let month = self.register::<RtcMth>().month().unwrap_or(1);
+
+        // The device treats every year divisible by 4 as a leap year, 2000 is one so that's the
+        // century to look the month length up in
+        let mut rtcdate: RtcDate = self.register();
+        let day = rtcdate.day().unwrap_or(1) + 1;
+        if day <= days_in_month(2000 + year as u16, month) {
+            rtcdate.set_day(day);
+            self.set_register(rtcdate);
+            return;
+        }
+
+        rtcdate.set_day(1);
+        self.set_register(rtcdate);
+
+        let mut rtcmth: RtcMth = self.register();
+        if month < 12 {
+            rtcmth.set_month(month + 1);
+            self.set_register(rtcmth);
+            return;
+        }
+
+        rtcmth.set_month(1);
+        self.set_register(rtcmth);
+
+        let mut rtcyear: RtcYear = self.register();
+        rtcyear.set_year((year + 1) % 100);
+        self.set_register(rtcyear);
+        self.update_leap_year();
+    }
+
+    /// Sets the interrupt flag of every enabled alarm whose match condition holds
+    fn check_alarms(&mut self) {
+        let control: Control = self.register();
+
+        for alarm in [Alarm::Zero, Alarm::One] {
+            if control.alarm_enabled(alarm) && self.alarm_matches(alarm) {
+                let mut almwkday: AlmWkday = self.alarm_register(alarm);
+                almwkday.set_almif(true);
+                self.set_alarm_register(alarm, almwkday);
+            }
+        }
+    }
+
+    fn alarm_matches(&self, alarm: Alarm) -> bool {
+        let seconds = self.registers[RtcSec::ADDRESS as usize] & 0x7F
+            == self.registers[AlmSec::address(alarm) as usize] & 0x7F;
+        let minutes = self.registers[RtcMin::ADDRESS as usize] & 0x7F
+            == self.registers[AlmMin::address(alarm) as usize] & 0x7F;
+        // Includes the AM/PM bit in 12-hour mode
+        let hours = self.registers[RtcHour::ADDRESS as usize] & 0x3F
+            == self.registers[AlmHour::address(alarm) as usize] & 0x3F;
+        let almwkday: AlmWkday = self.alarm_register(alarm);
+        let weekday = self.register::<RtcWkday>().weekday() == almwkday.weekday();
+        let date = self.registers[RtcDate::ADDRESS as usize] & 0x3F
+            == self.registers[AlmDate::address(alarm) as usize] & 0x3F;
+        let month = self.registers[RtcMth::ADDRESS as usize] & 0x1F
+            == self.registers[AlmMth::address(alarm) as usize] & 0x1F;
+
+        match almwkday.almmsk() {
+            0b000 => seconds,
+            0b001 => minutes,
+            0b010 => hours,
+            0b011 => weekday,
+            0b100 => date,
+            0b111 => seconds && minutes && hours && weekday && date && month,
+            _ => false,
+        }
+    }
+
+    fn mfp(&self) -> Option<bool> {
+        let control: Control = self.register();
+
+        if control.sqwen() {
+            return None;
+        }
+
+        if !control.alm0en() && !control.alm1en() {
+            return Some(control.out());
+        }
+
+        let asserted = [Alarm::Zero, Alarm::One].into_iter().any(|alarm| {
+            control.alarm_enabled(alarm) && self.alarm_register::<AlmWkday>(alarm).almif()
+        });
+        let almpol = self.alarm_register::<AlmWkday>(Alarm::Zero).almpol();
+
+        Some(asserted == almpol)
+    }
+
+    fn power_down(&mut self) {
+        if !self.powered {
+            return;
+        }
+        self.powered = false;
+
+        if self.register::<RtcWkday>().vbaten() {
+            self.log_timestamp(PowerFailEvent::Down);
+        } else {
+            self.registers.fill(0);
+            self.set_oscillator_running(false);
+        }
+    }
+
+    fn power_up(&mut self) {
+        if self.powered {
+            return;
+        }
+        self.powered = true;
+
+        if self.register::<RtcWkday>().vbaten() {
+            self.log_timestamp(PowerFailEvent::Up);
+
+            let mut rtcwkday: RtcWkday = self.register();
+            rtcwkday.set_pwrfail(true);
+            self.set_register(rtcwkday);
+        } else {
+            self.reset();
+        }
+    }
+
+    /// Latches the current time into a power-fail timestamp, unless PWRFAIL still holds the
+    /// previous one
+    fn log_timestamp(&mut self, event: PowerFailEvent) {
+        if self.register::<RtcWkday>().pwrfail() {
+            return;
+        }
+
+        let rtcmin: RtcMin = self.register();
+        let rtchour: RtcHour = self.register();
+        let rtcdate: RtcDate = self.register();
+
+        let mut pwrmth = PwrMth::default();
+        pwrmth.set_weekday(self.register::<RtcWkday>().weekday());
+        pwrmth.set_month(self.register::<RtcMth>().month().unwrap_or(1));
+
+        self.set_power_fail_register(event, PwrMin::from_bits(rtcmin.bits()));
+        self.set_power_fail_register(event, PwrHour::from_bits(rtchour.bits()));
+        self.set_power_fail_register(event, PwrDate::from_bits(rtcdate.bits()));
+        self.set_power_fail_register(event, pwrmth);
+    }
+}
+
+/// The address pointer wraps within the RTCC registers and within SRAM rather than running from
+/// one into the other
+fn next_address(address: u8) -> u8 {
+    match address {
+        RTCC_END => 0x00,
+        SRAM_END => SRAM_START,
+        address => address.wrapping_add(1),
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{Alarm, AlarmFlags, ClockConfig};
+
+/// Snapshot of the device state, read in a single transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Status {
+    /// Oscillator enable (ST) and clock source (EXTOSC)
+    pub clock: ClockConfig,
+    /// OSCRUN
+    pub oscillator_running: bool,
+    /// PWRFAIL
+    pub power_failed: bool,
+    /// VBATEN
+    pub battery_backup: bool,
+    /// SQWEN
+    pub square_wave: bool,
+    /// ALM0EN
+    pub alarm0_enabled: bool,
+    /// ALM1EN
+    pub alarm1_enabled: bool,
+    /// ALM0IF and ALM1IF
+    pub fired_alarms: AlarmFlags,
+}
+
+impl Status {
+    pub fn alarm_enabled(&self, alarm: Alarm) -> bool {
+        match alarm {
+            Alarm::Zero => self.alarm0_enabled,
+            Alarm::One => self.alarm1_enabled,
+        }
+    }
+}
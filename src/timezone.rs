@@ -0,0 +1,542 @@
+//! Time zones with daylight saving time rules, for keeping the RTC in UTC.
+
+use core::str::FromStr;
+
+use crate::datetime::{
+    days_from_civil, days_in_month, is_leap_year, weekday_from_days, SECONDS_PER_DAY,
+};
+use crate::{DateTime, Weekday};
+
+/// Transition time used by POSIX TZ strings when a rule doesn't give one, 02:00:00
+const DEFAULT_TRANSITION_TIME: i32 = 2 * 3600;
+
+/// UTC offset with optional daylight saving time, either built from constants or parsed from a
+/// POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`
+///
+/// Offsets are in seconds east of UTC, so the opposite sign of the POSIX TZ string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimeZone {
+    std_offset: i32,
+    dst: Option<Dst>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Dst {
+    offset: i32,
+    start: Transition,
+    end: Transition,
+}
+
+/// Local date and time a daylight saving time period starts or ends at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Transition {
+    pub date: TransitionDate,
+    /// Seconds after local midnight, in the time that is in effect before the transition. May be
+    /// negative or past 24 hours.
+    pub time: i32,
+}
+
+/// Day of the year a transition happens on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TransitionDate {
+    /// `weekday` of the `week`th week of `month`, week 5 being the last one (POSIX `Mm.w.d`)
+    MonthWeekday {
+        month: u8,
+        week: u8,
+        weekday: Weekday,
+    },
+    /// Day 1 through 365, never counting February 29 (POSIX `Jn`)
+    Julian(u16),
+    /// Day 0 through 365, counting February 29 in leap years (POSIX `n`)
+    DayOfYear(u16),
+}
+
+/// The string isn't a POSIX TZ string the parser supports
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidTimeZone;
+
+impl TimeZone {
+    pub const UTC: Self = Self::fixed(0);
+
+    /// A zone that stays at `offset` seconds east of UTC all year
+    pub const fn fixed(offset: i32) -> Self {
+        Self {
+            std_offset: offset,
+            dst: None,
+        }
+    }
+
+    /// A zone at `std_offset` seconds east of UTC that switches to `dst_offset` from `start`
+    /// until `end`. Southern hemisphere zones have `end` before `start` in the year.
+    pub const fn with_dst(
+        std_offset: i32,
+        dst_offset: i32,
+        start: Transition,
+        end: Transition,
+    ) -> Self {
+        Self {
+            std_offset,
+            dst: Some(Dst {
+                offset: dst_offset,
+                start,
+                end,
+            }),
+        }
+    }
+
+    /// Parses a POSIX TZ string. Zones with daylight saving time must give the transition
+    /// rules, as the default rules are implementation defined.
+    pub fn from_posix(tz: &str) -> Result<Self, InvalidTimeZone> {
+        Parser::new(tz).time_zone()
+    }
+
+    pub fn std_offset(&self) -> i32 {
+        self.std_offset
+    }
+
+    pub fn dst_offset(&self) -> Option<i32> {
+        self.dst.map(|dst| dst.offset)
+    }
+
+    /// Whether daylight saving time is in effect at `utc`
+    pub fn is_dst(&self, utc: DateTime) -> bool {
+        self.dst
+            .is_some_and(|dst| self.dst_active(&dst, utc.unix_timestamp()))
+    }
+
+    /// Offset in seconds east of UTC in effect at `utc`
+    pub fn offset_at(&self, utc: DateTime) -> i32 {
+        self.offset_at_timestamp(utc.unix_timestamp())
+    }
+
+    /// Converts `utc` to local time, or `None` if that falls outside of years 0 through 9999
+    pub fn to_local(&self, utc: DateTime) -> Option<DateTime> {
+        let timestamp = utc.unix_timestamp();
+
+        DateTime::from_unix_timestamp(timestamp + self.offset_at_timestamp(timestamp) as i64)
+    }
+
+    /// Converts `local` to UTC. A local time that happens twice when the clocks go back
+    /// resolves to the first one, and `None` is returned for local times skipped when the clocks
+    /// go forward.
+    pub fn to_utc(&self, local: DateTime) -> Option<DateTime> {
+        let timestamp = local.unix_timestamp();
+
+        // Try daylight saving time first, the earlier of the two instants if both match
+        self.dst
+            .map(|dst| dst.offset)
+            .into_iter()
+            .chain([self.std_offset])
+            .map(|offset| timestamp - offset as i64)
+            .find(|&utc| timestamp - utc == self.offset_at_timestamp(utc) as i64)
+            .and_then(DateTime::from_unix_timestamp)
+    }
+
+    fn offset_at_timestamp(&self, timestamp: i64) -> i32 {
+        match self.dst {
+            Some(dst) if self.dst_active(&dst, timestamp) => dst.offset,
+            _ => self.std_offset,
+        }
+    }
+
+    fn dst_active(&self, dst: &Dst, timestamp: i64) -> bool {
+        // The transitions are in local time, so pick the year on the standard time side
+        let year = match DateTime::from_unix_timestamp(timestamp + self.std_offset as i64) {
+            Some(local) => local.year(),
+            None => return false,
+        };
+        let start = dst.start.local_timestamp(year) - self.std_offset as i64;
+        let end = dst.end.local_timestamp(year) - dst.offset as i64;
+
+        if start <= end {
+            start <= timestamp && timestamp < end
+        } else {
+            timestamp < end || start <= timestamp
+        }
+    }
+}
+
+impl FromStr for TimeZone {
+    type Err = InvalidTimeZone;
+
+    fn from_str(tz: &str) -> Result<Self, Self::Err> {
+        Self::from_posix(tz)
+    }
+}
+
+impl Transition {
+    /// Local time of the transition in `year` as seconds since 1970-01-01 00:00:00
+    fn local_timestamp(&self, year: u16) -> i64 {
+        let new_year = days_from_civil(year, 1, 1) as i64;
+        let day = match self.date {
+            TransitionDate::MonthWeekday {
+                month,
+                week,
+                weekday,
+            } => {
+                let first = days_from_civil(year, month, 1);
+                let first_weekday = weekday_from_days(first);
+                let first = first as i64;
+                let mut day =
+                    first + weekday.days_since(first_weekday) as i64 + 7 * (week.max(1) as i64 - 1);
+
+                // Week 5 means the last one, which may be the fourth
+                while day - first >= days_in_month(year, month) as i64 {
+                    day -= 7;
+                }
+
+                day
+            }
+            TransitionDate::Julian(day) => {
+                let leap_day = (is_leap_year(year) && day >= 60) as i64;
+                new_year + day.max(1) as i64 - 1 + leap_day
+            }
+            TransitionDate::DayOfYear(day) => new_year + day as i64,
+        };
+
+        day * SECONDS_PER_DAY + self.time as i64
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tz: &'a str) -> Self {
+        Self {
+            bytes: tz.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn time_zone(&mut self) -> Result<TimeZone, InvalidTimeZone> {
+        self.name()?;
+        // POSIX offsets are west of UTC
+        let std_offset = -self.offset(24)?;
+
+        if self.peek().is_none() {
+            return Ok(TimeZone::fixed(std_offset));
+        }
+
+        self.name()?;
+        let dst_offset = match self.peek() {
+            Some(b'+' | b'-' | b'0'..=b'9') => -self.offset(24)?,
+            _ => std_offset + 3600,
+        };
+
+        self.expect(b',')?;
+        let start = self.transition()?;
+        self.expect(b',')?;
+        let end = self.transition()?;
+
+        if self.peek().is_some() {
+            return Err(InvalidTimeZone);
+        }
+
+        Ok(TimeZone::with_dst(std_offset, dst_offset, start, end))
+    }
+
+    /// Zone abbreviation, either alphabetic or quoted in angle brackets like `<+0330>`
+    fn name(&mut self) -> Result<(), InvalidTimeZone> {
+        let quoted = self.eat(b'<');
+        let start = self.pos;
+
+        while let Some(byte) = self.peek() {
+            let valid = if quoted {
+                byte.is_ascii_alphanumeric() || byte == b'+' || byte == b'-'
+            } else {
+                byte.is_ascii_alphabetic()
+            };
+
+            if !valid {
+                break;
+            }
+
+            self.pos += 1;
+        }
+
+        if self.pos - start < 3 || (quoted && !self.eat(b'>')) {
+            return Err(InvalidTimeZone);
+        }
+
+        Ok(())
+    }
+
+    fn transition(&mut self) -> Result<Transition, InvalidTimeZone> {
+        let date = if self.eat(b'M') {
+            let month = self.number(1, 12)? as u8;
+            self.expect(b'.')?;
+            let week = self.number(1, 5)? as u8;
+            self.expect(b'.')?;
+            // POSIX counts weekdays from Sunday
+            let weekday = Weekday::from_days_since_monday((self.number(0, 6)? + 6) as u8);
+
+            TransitionDate::MonthWeekday {
+                month,
+                week,
+                weekday,
+            }
+        } else if self.eat(b'J') {
+            TransitionDate::Julian(self.number(1, 365)? as u16)
+        } else {
+            TransitionDate::DayOfYear(self.number(0, 365)? as u16)
+        };
+
+        let time = if self.eat(b'/') {
+            // RFC 8536 extends the transition time to -167 through 167 hours
+            self.offset(167)?
+        } else {
+            DEFAULT_TRANSITION_TIME
+        };
+
+        Ok(Transition { date, time })
+    }
+
+    /// Signed `hh[:mm[:ss]]` in seconds
+    fn offset(&mut self, max_hours: u32) -> Result<i32, InvalidTimeZone> {
+        let sign = if self.eat(b'-') {
+            -1
+        } else {
+            self.eat(b'+');
+            1
+        };
+
+        let mut seconds = self.number(0, max_hours)? * 3600;
+        if self.eat(b':') {
+            seconds += self.number(0, 59)? * 60;
+
+            if self.eat(b':') {
+                seconds += self.number(0, 59)?;
+            }
+        }
+
+        Ok(sign * seconds as i32)
+    }
+
+    fn number(&mut self, min: u32, max: u32) -> Result<u32, InvalidTimeZone> {
+        let start = self.pos;
+        let mut value = 0u32;
+
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            value = value * 10 + (digit - b'0') as u32;
+            if value > max {
+                return Err(InvalidTimeZone);
+            }
+
+            self.pos += 1;
+        }
+
+        if self.pos == start || value < min {
+            return Err(InvalidTimeZone);
+        }
+
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == Some(byte);
+        if matched {
+            self.pos += 1;
+        }
+
+        matched
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), InvalidTimeZone> {
+        if self.eat(byte) {
+            Ok(())
+        } else {
+            Err(InvalidTimeZone)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimeZone, Transition, TransitionDate};
+    use crate::datetime::datetime;
+    use crate::{DateTime, Weekday};
+
+    const HOUR: i32 = 3600;
+
+    /// Checks that `utc` is the last second before the zone switches into or out of daylight
+    /// saving time
+    fn assert_transition(zone: &TimeZone, utc: DateTime, dst_before: bool) {
+        let after = utc.checked_add_seconds(1).unwrap();
+
+        assert_eq!(zone.is_dst(utc), dst_before);
+        assert_eq!(zone.is_dst(after), !dst_before);
+    }
+
+    #[test]
+    fn parse_fixed() {
+        assert_eq!(TimeZone::from_posix("UTC0"), Ok(TimeZone::UTC));
+        assert_eq!(TimeZone::from_posix("JST-9"), Ok(TimeZone::fixed(9 * HOUR)));
+        assert_eq!(
+            TimeZone::from_posix("<-0330>3:30"),
+            Ok(TimeZone::fixed(-(3 * HOUR + 30 * 60)))
+        );
+        assert_eq!(
+            TimeZone::from_posix("<+0545>-5:45"),
+            Ok(TimeZone::fixed(5 * HOUR + 45 * 60))
+        );
+    }
+
+    #[test]
+    fn parse_dst() {
+        let start = Transition {
+            date: TransitionDate::MonthWeekday {
+                month: 3,
+                week: 5,
+                weekday: Weekday::Sunday,
+            },
+            time: 2 * HOUR,
+        };
+        let end = Transition {
+            date: TransitionDate::MonthWeekday {
+                month: 10,
+                week: 5,
+                weekday: Weekday::Sunday,
+            },
+            time: 3 * HOUR,
+        };
+
+        assert_eq!(
+            "CET-1CEST,M3.5.0,M10.5.0/3".parse(),
+            Ok(TimeZone::with_dst(HOUR, 2 * HOUR, start, end))
+        );
+
+        let zone = TimeZone::from_posix("<-03>3<-02>,J60/-2,300/25:30").unwrap();
+        assert_eq!(zone.std_offset(), -3 * HOUR);
+        assert_eq!(zone.dst_offset(), Some(-2 * HOUR));
+        assert_eq!(
+            zone,
+            TimeZone::with_dst(
+                -3 * HOUR,
+                -2 * HOUR,
+                Transition {
+                    date: TransitionDate::Julian(60),
+                    time: -2 * HOUR,
+                },
+                Transition {
+                    date: TransitionDate::DayOfYear(300),
+                    time: 25 * HOUR + 30 * 60,
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for tz in [
+            "",
+            "CE-1",
+            "CET",
+            "CET-",
+            "CET-25",
+            "CET-1:60",
+            "CET-1CEST",
+            "CET-1CEST,M3.5.0",
+            "CET-1CEST,M13.5.0,M10.5.0",
+            "CET-1CEST,M3.6.0,M10.5.0",
+            "CET-1CEST,M3.5.7,M10.5.0",
+            "CET-1CEST,M3.5.0,M10.5.0/168",
+            "CET-1CEST,M3.5.0,M10.5.0/3x",
+            "CET-1CEST,J0,J365",
+            "CET-1CEST,0,366",
+            "<+05-5",
+            "<+5>-5",
+            "CET-1 ",
+        ] {
+            assert!(TimeZone::from_posix(tz).is_err(), "{tz}");
+        }
+    }
+
+    #[test]
+    fn central_europe_transitions() {
+        let zone = TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+
+        assert_transition(&zone, datetime(2024, 3, 31, 0, 59, 59), false);
+        assert_transition(&zone, datetime(2024, 10, 27, 0, 59, 59), true);
+        // The last Sunday falls on a different date every year
+        assert_transition(&zone, datetime(2026, 3, 29, 0, 59, 59), false);
+        assert_transition(&zone, datetime(2026, 10, 25, 0, 59, 59), true);
+
+        assert_eq!(zone.offset_at(datetime(2024, 1, 1, 0, 0, 0)), HOUR);
+        assert_eq!(zone.offset_at(datetime(2024, 7, 1, 0, 0, 0)), 2 * HOUR);
+    }
+
+    #[test]
+    fn australia_transitions_span_new_year() {
+        let zone = TimeZone::from_posix("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+
+        assert_transition(&zone, datetime(2024, 4, 6, 15, 59, 59), true);
+        assert_transition(&zone, datetime(2024, 10, 5, 15, 59, 59), false);
+
+        // Daylight saving time carries through New Year in both UTC and local time
+        assert!(zone.is_dst(datetime(2024, 12, 31, 12, 59, 59)));
+        assert!(zone.is_dst(datetime(2024, 12, 31, 13, 0, 0)));
+        assert_eq!(
+            zone.to_local(datetime(2024, 12, 31, 13, 0, 0)),
+            Some(datetime(2025, 1, 1, 0, 0, 0))
+        );
+        assert_eq!(zone.offset_at(datetime(2025, 7, 1, 0, 0, 0)), 10 * HOUR);
+    }
+
+    #[test]
+    fn julian_days() {
+        // J60 is March 1 whether or not the year is a leap year, day 59 is February 29 in one
+        let julian = TimeZone::from_posix("XST0XDT,J60/0,J300/0").unwrap();
+        let day_of_year = TimeZone::from_posix("XST0XDT,59/0,J300/0").unwrap();
+
+        assert_transition(&julian, datetime(2024, 2, 29, 23, 59, 59), false);
+        assert_transition(&julian, datetime(2023, 2, 28, 23, 59, 59), false);
+        assert_transition(&day_of_year, datetime(2024, 2, 28, 23, 59, 59), false);
+        assert_transition(&day_of_year, datetime(2023, 2, 28, 23, 59, 59), false);
+    }
+
+    #[test]
+    fn local_to_utc() {
+        let zone = TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+
+        assert_eq!(
+            zone.to_utc(datetime(2024, 3, 31, 1, 59, 59)),
+            Some(datetime(2024, 3, 31, 0, 59, 59))
+        );
+        // Skipped when the clocks go forward
+        assert_eq!(zone.to_utc(datetime(2024, 3, 31, 2, 0, 0)), None);
+        assert_eq!(zone.to_utc(datetime(2024, 3, 31, 2, 59, 59)), None);
+        assert_eq!(
+            zone.to_utc(datetime(2024, 3, 31, 3, 0, 0)),
+            Some(datetime(2024, 3, 31, 1, 0, 0))
+        );
+        // Happens twice when the clocks go back, the first one wins
+        assert_eq!(
+            zone.to_utc(datetime(2024, 10, 27, 2, 30, 0)),
+            Some(datetime(2024, 10, 27, 0, 30, 0))
+        );
+        assert_eq!(
+            zone.to_local(datetime(2024, 10, 27, 1, 30, 0)),
+            Some(datetime(2024, 10, 27, 2, 30, 0))
+        );
+    }
+
+    #[test]
+    fn conversions_at_range_limits() {
+        let zone = TimeZone::fixed(HOUR);
+
+        assert_eq!(zone.to_local(datetime(9999, 12, 31, 23, 0, 0)), None);
+        assert_eq!(zone.to_utc(datetime(0, 1, 1, 0, 0, 0)), None);
+    }
+}
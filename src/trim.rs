@@ -0,0 +1,61 @@
+use crate::registers::OscTrim;
+
+/// How often the digital trim is applied (CRSTRIM)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TrimMode {
+    /// Trim once per minute, roughly 1.017 ppm per step
+    Fine,
+    /// Trim 128 times per second, 7812.5 ppm per step. Meant for calibrating against the trimmed
+    /// 64 Hz MFP output rather than for normal timekeeping.
+    Coarse,
+}
+
+impl TrimMode {
+    /// Correction applied by a single TRIMVAL step. Each step adds or removes two cycles of the
+    /// 32.768 kHz clock, once per minute or 128 times per second.
+    pub fn ppm_per_step(self) -> f32 {
+        match self {
+            TrimMode::Fine => 2.0 / (32_768.0 * 60.0) * 1_000_000.0,
+            TrimMode::Coarse => 2.0 * 128.0 / 32_768.0 * 1_000_000.0,
+        }
+    }
+}
+
+/// Digital trim of the oscillator. Positive values add clock cycles to correct a slow
+/// oscillator, negative values remove cycles to correct a fast one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Trim {
+    pub ppm: f32,
+    pub mode: TrimMode,
+}
+
+impl Trim {
+    /// Encodes the trim as an OSCTRIM value, rounding to the nearest step. Returns `None` if
+    /// the trim is beyond the 127 steps the register can hold.
+    pub(crate) fn to_register(self) -> Option<OscTrim> {
+        let steps = self.ppm / self.mode.ppm_per_step();
+        let magnitude = if steps < 0.0 { -steps } else { steps };
+
+        if magnitude.is_nan() || magnitude >= 127.5 {
+            return None;
+        }
+
+        let mut osctrim = OscTrim::default();
+        osctrim.set_sign(steps > 0.0);
+        osctrim.set_trimval((magnitude + 0.5) as u8);
+
+        Some(osctrim)
+    }
+
+    pub(crate) fn from_register(osctrim: OscTrim, mode: TrimMode) -> Self {
+        let steps = osctrim.trimval() as f32;
+        let sign = if osctrim.sign() { 1.0 } else { -1.0 };
+
+        Self {
+            ppm: sign * steps * mode.ppm_per_step(),
+            mode,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Marker types for the parts in the MCP794xx family.
+//!
+//! The driver is generic over one of these, defaulting to [`Mcp7940N`], and only exposes the
+//! methods the selected part supports. All of them share the RTCC register map at I2C address
+//! `0x6F`.
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A part in the MCP794xx family
+pub trait Variant: sealed::Sealed {}
+
+/// Parts with a VBAT pin, supporting battery backup and the power-fail timestamps
+pub trait BatteryBackup: Variant {}
+
+/// Parts with an 8 byte protected EEPROM block at I2C address `0x57`, used to hold an EUI
+pub trait ProtectedEeprom: Variant {}
+
+/// Parts with 128 bytes of user EEPROM and the STATUS register at I2C address `0x57`
+pub trait UserEeprom: ProtectedEeprom {}
+
+/// Parts with an EUI-48 programmed into the protected EEPROM block at the factory
+pub trait FactoryEui48: ProtectedEeprom {}
+
+/// Parts with an EUI-64 programmed into the protected EEPROM block at the factory
+pub trait FactoryEui64: ProtectedEeprom {}
+
+/// MCP7940M, without battery backup
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp7940M;
+
+/// MCP7940N, with battery backup
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp7940N;
+
+/// MCP79400, with battery backup and a blank protected EEPROM block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp79400;
+
+/// MCP79401, with battery backup and an EUI-48 in the protected EEPROM block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp79401;
+
+/// MCP79402, with battery backup and an EUI-64 in the protected EEPROM block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp79402;
+
+/// MCP79410, with battery backup, 128 bytes of EEPROM and a blank protected EEPROM block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp79410;
+
+/// MCP79411, with battery backup, 128 bytes of EEPROM and an EUI-48 in the protected EEPROM
+/// block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp79411;
+
+/// MCP79412, with battery backup, 128 bytes of EEPROM and an EUI-64 in the protected EEPROM
+/// block
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Mcp79412;
+
+macro_rules! variant {
+    ($name:ident $(, $capability:ident)*) => {
+        impl sealed::Sealed for $name {}
+        impl Variant for $name {}
+        $(impl $capability for $name {})*
+    };
+}
+
+variant!(Mcp7940M);
+variant!(Mcp7940N, BatteryBackup);
+variant!(Mcp79400, BatteryBackup, ProtectedEeprom);
+variant!(Mcp79401, BatteryBackup, ProtectedEeprom, FactoryEui48);
+variant!(Mcp79402, BatteryBackup, ProtectedEeprom, FactoryEui64);
+variant!(Mcp79410, BatteryBackup, ProtectedEeprom, UserEeprom);
+variant!(
+    Mcp79411,
+    BatteryBackup,
+    ProtectedEeprom,
+    UserEeprom,
+    FactoryEui48
+);
+variant!(
+    Mcp79412,
+    BatteryBackup,
+    ProtectedEeprom,
+    UserEeprom,
+    FactoryEui64
+);
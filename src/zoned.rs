@@ -0,0 +1,98 @@
+use embedded_hal::i2c::I2c;
+#[cfg(feature = "async")]
+use embedded_hal_async::i2c::I2c as AsyncI2c;
+
+use crate::variant::{Mcp7940N, Variant};
+#[cfg(feature = "async")]
+use crate::Mcp7940nAsync;
+use crate::{DateTime, Error, Mcp7940n, TimeZone};
+
+/// Keeps the RTC in UTC and converts to and from local time in `zone`, so the stored time
+/// doesn't jump when daylight saving time starts or ends
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(ZonedRtc(async = "ZonedRtcAsync"), Mcp7940n(async = "Mcp7940nAsync"))
+    )
+)]
+#[derive(Debug)]
+pub struct ZonedRtc<I, V = Mcp7940N> {
+    rtc: Mcp7940n<I, V>,
+    zone: TimeZone,
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(ZonedRtc(async = "ZonedRtcAsync"), Mcp7940n(async = "Mcp7940nAsync"))
+    )
+)]
+impl<I, V: Variant> ZonedRtc<I, V> {
+    pub fn new(rtc: Mcp7940n<I, V>, zone: TimeZone) -> Self {
+        Self { rtc, zone }
+    }
+
+    pub fn set_zone(&mut self, zone: TimeZone) {
+        self.zone = zone;
+    }
+
+    pub fn zone(&self) -> TimeZone {
+        self.zone
+    }
+
+    /// The wrapped driver, for everything that isn't wall clock time. Times written through it
+    /// should be in UTC.
+    pub fn rtc(&mut self) -> &mut Mcp7940n<I, V> {
+        &mut self.rtc
+    }
+
+    pub fn destroy(self) -> Mcp7940n<I, V> {
+        self.rtc
+    }
+}
+
+#[maybe_async_cfg::maybe(
+    sync(all(), keep_self),
+    async(
+        feature = "async",
+        idents(
+            ZonedRtc(async = "ZonedRtcAsync"),
+            Mcp7940n(async = "Mcp7940nAsync"),
+            I2c(async = "AsyncI2c")
+        )
+    )
+)]
+impl<I: I2c, V: Variant> ZonedRtc<I, V> {
+    pub async fn now_utc(&mut self) -> Result<DateTime, Error<I::Error>> {
+        self.rtc.now().await
+    }
+
+    /// Reads the time and converts it to local time
+    pub async fn now_local(&mut self) -> Result<DateTime, Error<I::Error>> {
+        let utc = self.rtc.now().await?;
+
+        self.zone.to_local(utc).ok_or(Error::YearOutOfRange)
+    }
+
+    /// Reads the offset from UTC in seconds that is currently in effect
+    pub async fn offset(&mut self) -> Result<i32, Error<I::Error>> {
+        let utc = self.rtc.now().await?;
+
+        Ok(self.zone.offset_at(utc))
+    }
+
+    pub async fn set_utc(&mut self, utc: DateTime) -> Result<(), Error<I::Error>> {
+        self.rtc.set_datetime(utc).await
+    }
+
+    /// Sets the time from local time. A local time that happens twice when the clocks go back is
+    /// taken as the first one, and one skipped when the clocks go forward fails with
+    /// [`Error::InvalidDate`].
+    pub async fn set_local(&mut self, local: DateTime) -> Result<(), Error<I::Error>> {
+        let utc = self.zone.to_utc(local).ok_or(Error::InvalidDate)?;
+
+        self.rtc.set_datetime(utc).await
+    }
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use core::time::Duration;
+
+use common::{datetime, running, STARTUP};
+use mcp7940n::sim::Simulator;
+use mcp7940n::{
+    Alarm, AlarmConfig, AlarmFlags, AlarmMatch, AlarmPolarity, Error, MfpMode, Weekday,
+};
+
+#[test]
+fn full_match_sets_flag_and_drives_mfp() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_datetime(datetime(2024, 6, 1, 12, 29, 58)).unwrap();
+
+    let config = AlarmConfig::new(AlarmMatch::Full, &datetime(2024, 6, 1, 12, 30, 0));
+    rtc.set_alarm(Alarm::Zero, &config).unwrap();
+    rtc.set_alarm_polarity(AlarmPolarity::ActiveHigh).unwrap();
+    rtc.enable_alarm(Alarm::Zero).unwrap();
+    rtc.set_mfp_mode(MfpMode::AlarmInterrupt).unwrap();
+
+    sim.advance(STARTUP + Duration::from_secs(1));
+    assert_eq!(rtc.alarm_fired(Alarm::Zero), Ok(false));
+    assert_eq!(sim.mfp(), Some(false));
+
+    sim.advance(Duration::from_secs(1));
+    assert_eq!(rtc.alarm_fired(Alarm::Zero), Ok(true));
+    assert_eq!(rtc.alarm_fired(Alarm::One), Ok(false));
+    assert_eq!(sim.mfp(), Some(true));
+
+    // Clearing the flag while the alarm still matches sets it again
+    rtc.clear_alarm_flags().unwrap();
+    assert_eq!(rtc.alarm_fired(Alarm::Zero), Ok(true));
+
+    sim.advance(Duration::from_secs(1));
+    assert_eq!(
+        rtc.acknowledge_alarms(),
+        Ok(AlarmFlags {
+            alarm0: true,
+            alarm1: false
+        })
+    );
+    assert_eq!(rtc.fired_alarms(), Ok(AlarmFlags::default()));
+    assert_eq!(sim.mfp(), Some(false));
+}
+
+#[test]
+fn disabled_alarm_does_not_fire() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_datetime(datetime(2024, 6, 1, 12, 0, 0)).unwrap();
+
+    let config = AlarmConfig::new(AlarmMatch::Minutes, &datetime(2024, 6, 1, 12, 1, 0));
+    rtc.set_alarm(Alarm::One, &config).unwrap();
+
+    sim.advance(STARTUP + Duration::from_secs(120));
+    assert_eq!(rtc.alarm_fired(Alarm::One), Ok(false));
+}
+
+#[test]
+fn clear_flag_keeps_configuration() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_datetime(datetime(2024, 6, 1, 12, 0, 0)).unwrap();
+
+    let config = AlarmConfig::new(AlarmMatch::Seconds, &datetime(2024, 6, 1, 12, 0, 5));
+    rtc.set_alarm(Alarm::One, &config).unwrap();
+    rtc.enable_alarm(Alarm::One).unwrap();
+
+    sim.advance(STARTUP + Duration::from_secs(5));
+    assert_eq!(rtc.alarm_fired(Alarm::One), Ok(true));
+
+    sim.advance(Duration::from_secs(1));
+    rtc.clear_alarm_flag(Alarm::One).unwrap();
+    assert_eq!(rtc.alarm_fired(Alarm::One), Ok(false));
+    assert_eq!(rtc.alarm(Alarm::One), Ok(config));
+    assert_eq!(rtc.alarm_enabled(Alarm::One), Ok(true));
+
+    // Matching seconds fires again once a minute
+    sim.advance(Duration::from_secs(59));
+    assert_eq!(rtc.alarm_fired(Alarm::One), Ok(true));
+}
+
+#[test]
+fn alarm_round_trip() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+
+    let config = AlarmConfig {
+        matches: AlarmMatch::Weekday,
+        hour: 7,
+        minute: 45,
+        second: 30,
+        weekday: Weekday::Friday,
+        day: 13,
+        month: 9,
+    };
+    rtc.set_alarm(Alarm::Zero, &config).unwrap();
+
+    assert_eq!(rtc.alarm(Alarm::Zero), Ok(config));
+}
+
+#[test]
+fn invalid_alarm_rejected() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+
+    let mut config = AlarmConfig::new(AlarmMatch::Full, &datetime(2024, 6, 1, 12, 0, 0));
+    config.hour = 24;
+
+    assert_eq!(rtc.set_alarm(Alarm::Zero, &config), Err(Error::InvalidDate));
+}
+
+#[test]
+fn reserved_mask_reported() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+
+    // ALM0WKDAY with ALMMSK set to the reserved 0b101
+    sim.poke(0x0D, 0b0101_0001);
+
+    assert_eq!(rtc.alarm(Alarm::Zero), Err(Error::InvalidAlarmMask));
+}
+
+#[test]
+fn out_of_range_alarm_reported() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+
+    let config = AlarmConfig::new(AlarmMatch::Full, &datetime(2024, 6, 1, 12, 0, 0));
+    rtc.set_alarm(Alarm::Zero, &config).unwrap();
+
+    // ALM0HOUR holding hour 25, which is valid BCD
+    sim.poke(0x0C, 0x25);
+    assert_eq!(rtc.alarm(Alarm::Zero), Err(Error::InvalidDate));
+
+    rtc.set_alarm(Alarm::Zero, &config).unwrap();
+
+    // ALM0MTH holding month 13
+    sim.poke(0x0F, 0x13);
+    assert_eq!(rtc.alarm(Alarm::Zero), Err(Error::InvalidDate));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+
+use common::{datetime, STARTUP};
+use mcp7940n::sim::Simulator;
+use mcp7940n::{Alarm, AlarmConfig, AlarmMatch, ClockSource, Mcp7940nAsync};
+
+/// Runs `future` to completion. The simulator never blocks, so nothing needs to wake it.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn set_and_read_time() {
+    let sim = Simulator::new();
+    let mut rtc = Mcp7940nAsync::new(&sim);
+
+    block_on(async {
+        rtc.start_oscillator(ClockSource::ExtCrystal, &mut sim.delay(), 100)
+            .await
+            .unwrap();
+
+        rtc.set_datetime(datetime(2024, 6, 1, 12, 30, 0))
+            .await
+            .unwrap();
+        sim.advance(STARTUP + Duration::from_secs(5));
+
+        assert_eq!(rtc.now().await, Ok(datetime(2024, 6, 1, 12, 30, 5)));
+    });
+}
+
+#[test]
+fn alarm_fires() {
+    let sim = Simulator::new();
+    let mut rtc = Mcp7940nAsync::new(&sim);
+
+    block_on(async {
+        rtc.start_oscillator(ClockSource::ExtCrystal, &mut sim.delay(), 100)
+            .await
+            .unwrap();
+        rtc.set_datetime(datetime(2024, 6, 1, 12, 29, 59))
+            .await
+            .unwrap();
+
+        let config = AlarmConfig::new(AlarmMatch::Minutes, &datetime(2024, 6, 1, 12, 30, 0));
+        rtc.set_alarm(Alarm::Zero, &config).await.unwrap();
+        rtc.enable_alarm(Alarm::Zero).await.unwrap();
+
+        sim.advance(STARTUP + Duration::from_secs(1));
+
+        let fired = rtc.acknowledge_alarms().await.unwrap();
+        assert!(fired.alarm0);
+        assert!(!fired.alarm1);
+    });
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use core::time::Duration;
+
+use common::{datetime, running, STARTUP};
+use mcp7940n::sim::Simulator;
+use mcp7940n::{
+    Alarm, AlarmConfig, AlarmMatch, ClockSource, Error, HourMode, Mcp7940n, MfpMode,
+    SquareWaveFrequency, Trim, TrimMode, Weekday, SRAM_SIZE,
+};
+
+#[test]
+fn twelve_hour_mode_converts_time_and_alarms() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_datetime(datetime(2024, 6, 1, 15, 30, 0)).unwrap();
+    let config = AlarmConfig::new(AlarmMatch::Hours, &datetime(2024, 6, 1, 0, 0, 0));
+    rtc.set_alarm(Alarm::Zero, &config).unwrap();
+
+    rtc.set_hour_mode(HourMode::TwelveHour).unwrap();
+
+    assert_eq!(rtc.hour_mode(), Ok(HourMode::TwelveHour));
+    // 12-hour flag, PM and 3
+    assert_eq!(sim.peek(0x02), 0b0110_0011);
+    // 12 AM
+    assert_eq!(sim.peek(0x0C), 0b0101_0010);
+    assert_eq!(rtc.now(), Ok(datetime(2024, 6, 1, 15, 30, 0)));
+    assert_eq!(rtc.alarm(Alarm::Zero), Ok(config));
+
+    // The clock is stopped for the conversion and restarted afterwards
+    sim.advance(STARTUP + Duration::from_secs(1));
+    assert_eq!(rtc.now(), Ok(datetime(2024, 6, 1, 15, 30, 1)));
+
+    // The mode is kept when setting the time
+    rtc.set_datetime(datetime(2024, 6, 1, 11, 59, 59)).unwrap();
+    sim.advance(STARTUP + Duration::from_secs(1));
+    assert_eq!(sim.peek(0x02), 0b0111_0010);
+    assert_eq!(rtc.now(), Ok(datetime(2024, 6, 1, 12, 0, 0)));
+
+    rtc.set_hour_mode(HourMode::TwentyFourHour).unwrap();
+    assert_eq!(sim.peek(0x02), 0x12);
+    assert_eq!(rtc.alarm(Alarm::Zero), Ok(config));
+}
+
+#[test]
+fn day_rollover_advances_weekday() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_first_weekday(Weekday::Sunday);
+
+    rtc.set_datetime(datetime(2024, 6, 1, 23, 59, 59)).unwrap();
+    assert_eq!(rtc.weekday(), Ok(Weekday::Saturday));
+    assert_eq!(sim.peek(0x03) & 0x07, 7);
+
+    sim.advance(STARTUP + Duration::from_secs(1));
+    assert_eq!(rtc.weekday(), Ok(Weekday::Sunday));
+    assert_eq!(rtc.weekday_matches_date(), Ok(true));
+}
+
+#[test]
+fn century_tracking_persists_in_sram() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_century_tracking(Some(10));
+    rtc.set_datetime(datetime(2199, 12, 31, 23, 59, 59))
+        .unwrap();
+    sim.advance(STARTUP + Duration::from_secs(1));
+
+    // A fresh driver picks up the century from SRAM
+    let mut rtc = Mcp7940n::new(&sim);
+    rtc.set_century_tracking(Some(10));
+    assert_eq!(rtc.now(), Ok(datetime(2200, 1, 1, 0, 0, 0)));
+    assert_eq!(rtc.century(), 22);
+}
+
+#[test]
+fn year_outside_century_rejected() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+
+    assert_eq!(
+        rtc.set_datetime(datetime(2100, 1, 1, 0, 0, 0)),
+        Err(Error::YearOutOfRange)
+    );
+}
+
+#[test]
+fn oscillator_fallback() {
+    let sim = Simulator::new();
+    sim.set_crystal(false);
+    let mut rtc = Mcp7940n::new(&sim);
+
+    assert_eq!(
+        rtc.start_oscillator_with_fallback(&mut sim.delay(), 100),
+        Err(Error::OscillatorStartTimeout)
+    );
+
+    sim.set_external_clock(true);
+    assert_eq!(
+        rtc.start_oscillator_with_fallback(&mut sim.delay(), 100),
+        Ok(ClockSource::ExtClock)
+    );
+    assert_eq!(rtc.osc_running(), Ok(true));
+}
+
+#[test]
+fn sram_round_trip() {
+    let sim = Simulator::new();
+    let mut rtc = Mcp7940n::new(&sim);
+
+    rtc.write_sram(60, &[1, 2, 3, 4]).unwrap();
+    let mut data = [0u8; 4];
+    rtc.read_sram(60, &mut data).unwrap();
+    assert_eq!(data, [1, 2, 3, 4]);
+
+    assert_eq!(rtc.write_sram(61, &[0; 4]), Err(Error::SramOutOfBounds));
+
+    rtc.fill_sram(0xAA).unwrap();
+    let mut data = [0u8; SRAM_SIZE];
+    rtc.read_sram(0, &mut data).unwrap();
+    assert!(data.iter().all(|&byte| byte == 0xAA));
+}
+
+#[test]
+fn coarse_trim_conflicts_with_square_wave() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+
+    rtc.set_mfp_mode(MfpMode::SquareWave(SquareWaveFrequency::Hz1))
+        .unwrap();
+
+    let coarse = Trim {
+        ppm: 10.0,
+        mode: TrimMode::Coarse,
+    };
+    assert_eq!(rtc.set_trim(&coarse), Err(Error::MfpConflict));
+    assert_eq!(
+        rtc.mfp_mode(),
+        Ok(MfpMode::SquareWave(SquareWaveFrequency::Hz1))
+    );
+
+    let fine = Trim {
+        ppm: 10.0,
+        mode: TrimMode::Fine,
+    };
+    rtc.set_trim(&fine).unwrap();
+    assert_eq!(rtc.trim().map(|trim| trim.mode), Ok(TrimMode::Fine));
+}
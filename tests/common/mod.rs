@@ -0,0 +1,24 @@
+// Not every test binary uses every helper
+#![allow(dead_code)]
+
+use core::time::Duration;
+use mcp7940n::sim::Simulator;
+
+use mcp7940n::{ClockSource, DateTime, Mcp7940n};
+
+/// Default oscillator startup time of the simulator, which passes again every time the clock is
+/// restarted after setting the time
+pub const STARTUP: Duration = Duration::from_millis(1);
+
+pub fn datetime(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> DateTime {
+    DateTime::new(year, month, day, hour, minute, second).unwrap()
+}
+
+/// Driver on `sim` with the crystal oscillator started
+pub fn running(sim: &Simulator) -> Mcp7940n<&Simulator> {
+    let mut rtc = Mcp7940n::new(sim);
+    rtc.start_oscillator(ClockSource::ExtCrystal, &mut sim.delay(), 100)
+        .unwrap();
+
+    rtc
+}
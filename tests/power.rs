@@ -0,0 +1,90 @@
+mod common;
+
+use core::time::Duration;
+
+use common::{datetime, running, STARTUP};
+use mcp7940n::sim::Simulator;
+use mcp7940n::{Error, Mcp7940n, PowerFailWindow, Weekday};
+
+#[test]
+fn power_fail_timestamps_logged_on_battery() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_datetime(datetime(2024, 12, 31, 23, 30, 0)).unwrap();
+    rtc.set_battery_backup(true).unwrap();
+    sim.advance(STARTUP);
+
+    assert_eq!(rtc.power_failed(), Ok(false));
+    assert_eq!(rtc.power_fail_timestamps(), Ok(None));
+
+    sim.advance(Duration::from_secs(5 * 60));
+    sim.power_down();
+    sim.advance(Duration::from_secs(2 * 3600));
+    sim.power_up();
+
+    assert_eq!(rtc.power_failed(), Ok(true));
+
+    let timestamps = rtc.power_fail_timestamps().unwrap().unwrap();
+    assert_eq!(timestamps.power_down.weekday, Weekday::Tuesday);
+    assert_eq!(timestamps.power_up.weekday, Weekday::Wednesday);
+
+    // The timestamps have no year, so the power-down is resolved into the previous year
+    assert_eq!(
+        rtc.power_fail_window(),
+        Ok(Some(PowerFailWindow {
+            power_down: datetime(2024, 12, 31, 23, 35, 0),
+            power_up: datetime(2025, 1, 1, 1, 35, 0),
+        }))
+    );
+    assert_eq!(rtc.now(), Ok(datetime(2025, 1, 1, 1, 35, 0)));
+
+    rtc.clear_power_fail().unwrap();
+    assert_eq!(rtc.power_failed(), Ok(false));
+    assert_eq!(rtc.power_fail_window(), Ok(None));
+    assert_eq!(rtc.battery_backup_enabled(), Ok(true));
+}
+
+#[test]
+fn first_power_failure_is_kept_until_cleared() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_datetime(datetime(2024, 6, 1, 8, 0, 0)).unwrap();
+    rtc.set_battery_backup(true).unwrap();
+    sim.advance(STARTUP);
+
+    sim.power_down();
+    sim.advance(Duration::from_secs(60));
+    sim.power_up();
+    sim.advance(Duration::from_secs(60));
+    sim.power_down();
+    sim.advance(Duration::from_secs(60));
+    sim.power_up();
+
+    let window = rtc.power_fail_window().unwrap().unwrap();
+    assert_eq!(window.power_down, datetime(2024, 6, 1, 8, 0, 0));
+}
+
+#[test]
+fn time_lost_without_battery() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_datetime(datetime(2024, 6, 1, 8, 0, 0)).unwrap();
+
+    sim.power_down();
+    assert!(rtc.now().is_err());
+    sim.power_up();
+
+    assert_eq!(rtc.power_failed(), Ok(false));
+    assert_eq!(rtc.now(), Err(Error::OscillatorNotRunning));
+}
+
+#[test]
+fn set_datetime_keeps_battery_backup() {
+    let sim = Simulator::new();
+    let mut rtc = Mcp7940n::new(&sim);
+    rtc.set_battery_backup(true).unwrap();
+
+    rtc.set_datetime(datetime(2024, 6, 1, 8, 0, 0)).unwrap();
+
+    assert_eq!(rtc.battery_backup_enabled(), Ok(true));
+}
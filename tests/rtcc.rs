@@ -0,0 +1,50 @@
+mod common;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use common::{datetime, running};
+use mcp7940n::sim::Simulator;
+use mcp7940n::Weekday;
+use rtcc::{DateTimeAccess, Rtcc};
+
+fn naive(year: i32, month: u32, day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .unwrap()
+        .and_hms_opt(12, 30, 0)
+        .unwrap()
+}
+
+#[test]
+fn weekday_numbered_from_sunday() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+
+    // 2024-06-02 was a Sunday
+    DateTimeAccess::set_datetime(&mut rtc, &naive(2024, 6, 2)).unwrap();
+    assert_eq!(Rtcc::weekday(&mut rtc), Ok(1));
+    assert_eq!(DateTimeAccess::datetime(&mut rtc), Ok(naive(2024, 6, 2)));
+
+    Rtcc::set_date(&mut rtc, &naive(2024, 6, 8).date()).unwrap();
+    assert_eq!(Rtcc::weekday(&mut rtc), Ok(7));
+
+    // The driver's own methods agree once it numbers from Sunday too
+    rtc.set_first_weekday(Weekday::Sunday);
+    assert_eq!(rtc.weekday(), Ok(Weekday::Saturday));
+    assert_eq!(rtc.weekday_matches_date(), Ok(true));
+
+    Rtcc::set_weekday(&mut rtc, 2).unwrap();
+    assert_eq!(rtc.weekday(), Ok(Weekday::Monday));
+}
+
+#[test]
+fn set_datetime_ignores_first_weekday() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_first_weekday(Weekday::Monday);
+
+    // 2024-06-05 was a Wednesday
+    DateTimeAccess::set_datetime(&mut rtc, &naive(2024, 6, 5)).unwrap();
+    assert_eq!(Rtcc::weekday(&mut rtc), Ok(4));
+
+    rtc.set_datetime(datetime(2024, 6, 5, 12, 30, 0)).unwrap();
+    assert_eq!(Rtcc::weekday(&mut rtc), Ok(3));
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use core::time::Duration;
+
+use common::{datetime, running, STARTUP};
+use mcp7940n::sim::Simulator;
+use mcp7940n::{Error, Mcp7940n};
+
+#[test]
+fn now_right_after_set() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    // A crystal can take hundreds of milliseconds to start up again
+    sim.set_startup_delay(Duration::from_millis(500));
+
+    rtc.set_datetime(datetime(2024, 6, 1, 12, 30, 0)).unwrap();
+
+    assert!(!rtc.osc_running().unwrap());
+    assert_eq!(rtc.now(), Ok(datetime(2024, 6, 1, 12, 30, 0)));
+
+    rtc.set_unix(946_684_800).unwrap();
+    assert_eq!(rtc.now_unix(), Ok(946_684_800));
+
+    rtc.adjust_by(Duration::from_secs(90)).unwrap();
+    assert_eq!(rtc.now(), Ok(datetime(2000, 1, 1, 0, 1, 30)));
+}
+
+#[test]
+fn now_with_oscillator_disabled() {
+    let sim = Simulator::new();
+    let mut rtc = Mcp7940n::new(&sim);
+
+    rtc.set_datetime(datetime(2024, 6, 1, 12, 30, 0)).unwrap();
+
+    assert_eq!(rtc.now(), Err(Error::OscillatorNotRunning));
+}
+
+#[test]
+fn century_rollover_into_year_that_is_not_leap() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_century_tracking(Some(0));
+
+    rtc.set_datetime(datetime(2099, 12, 31, 23, 59, 59))
+        .unwrap();
+    sim.advance(STARTUP + Duration::from_secs(1));
+
+    assert_eq!(rtc.now(), Ok(datetime(2100, 1, 1, 0, 0, 0)));
+    assert_eq!(rtc.century(), 21);
+}
+
+#[test]
+fn leap_day_counted_by_device_reads_as_march_first() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_century(21);
+
+    rtc.set_datetime(datetime(2100, 2, 28, 23, 59, 59)).unwrap();
+    sim.advance(STARTUP + Duration::from_secs(1));
+    assert_eq!(rtc.now(), Ok(datetime(2100, 3, 1, 0, 0, 0)));
+
+    // The device was moved on to March 1, so the next day is March 2
+    sim.advance(STARTUP + Duration::from_secs(86_400));
+    assert_eq!(rtc.now(), Ok(datetime(2100, 3, 2, 0, 0, 0)));
+}
+
+#[test]
+fn set_in_year_that_is_not_leap() {
+    let sim = Simulator::new();
+    let mut rtc = running(&sim);
+    rtc.set_century(21);
+
+    rtc.set_datetime(datetime(2100, 1, 15, 8, 0, 0)).unwrap();
+    assert_eq!(rtc.now(), Ok(datetime(2100, 1, 15, 8, 0, 0)));
+
+    rtc.set_datetime(datetime(2100, 3, 15, 8, 0, 0)).unwrap();
+    assert_eq!(rtc.now(), Ok(datetime(2100, 3, 15, 8, 0, 0)));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use core::time::Duration;
+
+use common::{datetime, running, STARTUP};
+use mcp7940n::sim::Simulator;
+use mcp7940n::{Error, TimeZone, ZonedRtc};
+
+const CET: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+#[test]
+fn local_time_across_dst_start() {
+    let sim = Simulator::new();
+    let mut rtc = ZonedRtc::new(running(&sim), TimeZone::from_posix(CET).unwrap());
+
+    rtc.set_local(datetime(2024, 3, 31, 1, 59, 58)).unwrap();
+    assert_eq!(rtc.now_utc(), Ok(datetime(2024, 3, 31, 0, 59, 58)));
+    assert_eq!(rtc.offset(), Ok(3600));
+
+    sim.advance(STARTUP + Duration::from_secs(3));
+
+    assert_eq!(rtc.now_local(), Ok(datetime(2024, 3, 31, 3, 0, 1)));
+    assert_eq!(rtc.now_utc(), Ok(datetime(2024, 3, 31, 1, 0, 1)));
+    assert_eq!(rtc.offset(), Ok(7200));
+
+    // 02:30 never happens that night
+    assert_eq!(
+        rtc.set_local(datetime(2024, 3, 31, 2, 30, 0)),
+        Err(Error::InvalidDate)
+    );
+    assert_eq!(rtc.now_utc(), Ok(datetime(2024, 3, 31, 1, 0, 1)));
+}
+
+#[test]
+fn local_time_across_dst_end() {
+    let sim = Simulator::new();
+    let mut rtc = ZonedRtc::new(running(&sim), TimeZone::from_posix(CET).unwrap());
+
+    // 02:59:58 happens twice, the first one is still summer time
+    rtc.set_local(datetime(2024, 10, 27, 2, 59, 58)).unwrap();
+    assert_eq!(rtc.now_utc(), Ok(datetime(2024, 10, 27, 0, 59, 58)));
+
+    sim.advance(STARTUP + Duration::from_secs(3));
+
+    assert_eq!(rtc.now_local(), Ok(datetime(2024, 10, 27, 2, 0, 1)));
+    assert_eq!(rtc.now_utc(), Ok(datetime(2024, 10, 27, 1, 0, 1)));
+    assert_eq!(rtc.offset(), Ok(3600));
+}